@@ -0,0 +1,69 @@
+//! Specialization of ISF shaders by baking input values into the GLSL as constants.
+
+use crate::{glsl, InputType, InputValue, Isf, UniformType};
+use std::collections::BTreeMap;
+
+impl Isf {
+    /// Bake the given input values into the shader body as constant literals, removing the
+    /// respective inputs, e.g. to ship a "locked" variant of a shader.
+    ///
+    /// Every reference to a baked input's uniform within `body` is replaced with a GLSL literal
+    /// of the uniform's type, e.g. `vec2(0.5, 0.25)` for a `point2D`. References are matched as
+    /// whole identifiers, ignoring comments and field selections. Negative values are wrapped in
+    /// parentheses so that they may appear anywhere an identifier could. Any `uniform` declaration
+    /// of a baked input within `body` is removed.
+    ///
+    /// Values for inputs that do not exist, values whose type does not match the input and values
+    /// for inputs bound as textures (`image`, `audio` and `audioFFT`) are ignored, leaving the
+    /// respective inputs untouched.
+    pub fn bake_constants(&mut self, values: &BTreeMap<String, InputValue>, body: &mut String) {
+        let mut baked = vec![];
+        for input in &self.inputs {
+            let literal = match values.get(&input.name) {
+                None => continue,
+                Some(value) => match glsl_literal(&input.ty, value) {
+                    None => continue,
+                    Some(literal) => literal,
+                },
+            };
+            *body = glsl::remove_uniform_declaration(body, &input.name);
+            *body = glsl::replace_identifier(body, &input.name, &literal);
+            baked.push(input.name.clone());
+        }
+        self.inputs.retain(|input| !baked.contains(&input.name));
+    }
+}
+
+/// Produce the GLSL literal for the value if it matches the uniform type of the input.
+fn glsl_literal(ty: &InputType, value: &InputValue) -> Option<String> {
+    let literal = match (ty.uniform_type()?, *value) {
+        (UniformType::Bool, InputValue::Bool(b)) | (UniformType::Bool, InputValue::Event(b)) => {
+            b.to_string()
+        }
+        (UniformType::Int, InputValue::Long(l)) => wrap_negative(l.to_string()),
+        (UniformType::Float, InputValue::Float(f)) => float_literal(f),
+        (UniformType::Vec2, InputValue::Point2d(p)) => vector_literal("vec2", &p),
+        (UniformType::Vec3, InputValue::Color(c)) => vector_literal("vec3", &c[..3]),
+        (UniformType::Vec4, InputValue::Color(c)) => vector_literal("vec4", &c),
+        _ => return None,
+    };
+    Some(literal)
+}
+
+fn float_literal(f: f32) -> String {
+    // The `Debug` format always includes a decimal point or exponent, as required by GLSL.
+    wrap_negative(format!("{:?}", f))
+}
+
+fn vector_literal(ty: &str, components: &[f32]) -> String {
+    let components: Vec<_> = components.iter().map(|&f| format!("{:?}", f)).collect();
+    format!("{}({})", ty, components.join(", "))
+}
+
+fn wrap_negative(literal: String) -> String {
+    if literal.starts_with('-') {
+        format!("({})", literal)
+    } else {
+        literal
+    }
+}
@@ -0,0 +1,293 @@
+//! Builders for constructing ISF types programmatically.
+
+use crate::{ImageImport, Input, InputType, Isf, Pass, ValidationError};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// A builder for an [**Isf**](./struct.Isf.html).
+///
+/// ```
+/// use isf::{Input, InputFloat, InputType, Isf, Pass};
+///
+/// let isf = Isf::builder()
+///     .isfvsn("2.0")
+///     .description("Blurs the input image.")
+///     .category("Blur")
+///     .input(Input::builder("inputImage", InputType::Image))
+///     .input(
+///         Input::builder("radius", InputType::Float(InputFloat::default().with_default(0.5)))
+///             .label("Radius"),
+///     )
+///     .pass(Pass::builder().target("blur").width_expr("$WIDTH / 2"))
+///     .pass(Pass::to_screen())
+///     .build();
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let json = serde_json::to_string(&isf).unwrap();
+/// assert_eq!(serde_json::from_str::<Isf>(&json).unwrap(), isf);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct IsfBuilder {
+    isf: Isf,
+}
+
+/// A builder for an [**Input**](./struct.Input.html).
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputBuilder {
+    input: Input,
+}
+
+/// A builder for a [**Pass**](./struct.Pass.html).
+///
+/// ```
+/// let pass = isf::Pass::builder()
+///     .target("blur")
+///     .float(true)
+///     .width_expr("$WIDTH / 2")
+///     .height_expr("$HEIGHT / 2")
+///     .build();
+/// assert_eq!(pass.target.as_deref(), Some("blur"));
+///
+/// // A persistent pass must have a target.
+/// assert!(isf::Pass::builder().persistent(true).try_build().is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PassBuilder {
+    pass: Pass,
+}
+
+impl Pass {
+    /// A pass rendering directly to the output at the render size.
+    pub fn to_screen() -> Self {
+        Pass {
+            target: None,
+            persistent: false,
+            float: false,
+            width: None,
+            height: None,
+            width_is_number: false,
+            height_is_number: false,
+        }
+    }
+
+    /// A pass rendering to the target buffer with the given name at the render size.
+    pub fn to_target(name: impl Into<String>) -> Self {
+        Pass {
+            target: Some(name.into()),
+            ..Pass::to_screen()
+        }
+    }
+
+    /// Begin building a pass, starting from [**to_screen**](#method.to_screen).
+    pub fn builder() -> PassBuilder {
+        PassBuilder {
+            pass: Pass::to_screen(),
+        }
+    }
+}
+
+impl PassBuilder {
+    /// Render to the target buffer with the given name.
+    pub fn target(mut self, name: impl Into<String>) -> Self {
+        self.pass.target = Some(name.into());
+        self
+    }
+
+    /// Whether or not the target persists between frames.
+    pub fn persistent(mut self, persistent: bool) -> Self {
+        self.pass.persistent = persistent;
+        self
+    }
+
+    /// Whether or not the target stores 32-bit floating point values.
+    pub fn float(mut self, float: bool) -> Self {
+        self.pass.float = float;
+        self
+    }
+
+    /// The expression describing the width of the target, e.g. `"$WIDTH / 2"`.
+    pub fn width_expr(mut self, expr: impl Into<String>) -> Self {
+        self.pass.width = Some(expr.into());
+        self.pass.width_is_number = false;
+        self
+    }
+
+    /// The expression describing the height of the target, e.g. `"$HEIGHT / 2"`.
+    pub fn height_expr(mut self, expr: impl Into<String>) -> Self {
+        self.pass.height = Some(expr.into());
+        self.pass.height_is_number = false;
+        self
+    }
+
+    /// A fixed width for the target, written as a JSON number, e.g. `"WIDTH": 512`.
+    pub fn width(mut self, width: f64) -> Self {
+        self.pass.width = Some(width.to_string());
+        self.pass.width_is_number = true;
+        self
+    }
+
+    /// A fixed height for the target, written as a JSON number, e.g. `"HEIGHT": 512`.
+    pub fn height(mut self, height: f64) -> Self {
+        self.pass.height = Some(height.to_string());
+        self.pass.height_is_number = true;
+        self
+    }
+
+    /// The expressions describing the width and height of the target.
+    pub fn dimensions(self, width_expr: impl Into<String>, height_expr: impl Into<String>) -> Self {
+        self.width_expr(width_expr).height_expr(height_expr)
+    }
+
+    /// Produce the `Pass`.
+    pub fn build(self) -> Pass {
+        self.pass
+    }
+
+    /// Produce the `Pass`, first checking that a persistent pass has a target and that the width
+    /// and height expressions parse.
+    ///
+    /// These are the per-pass checks performed by
+    /// [**Isf::validate**](./struct.Isf.html#method.validate). As the position of the pass is not
+    /// yet known, errors refer to pass `0`.
+    pub fn try_build(self) -> Result<Pass, Vec<ValidationError>> {
+        let errors = self.pass.errors(0, true);
+        if errors.is_empty() {
+            Ok(self.pass)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl From<PassBuilder> for Pass {
+    fn from(builder: PassBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl Isf {
+    /// Begin building a shader without any metadata, inputs or passes.
+    pub fn builder() -> IsfBuilder {
+        IsfBuilder {
+            isf: Isf::default(),
+        }
+    }
+}
+
+impl IsfBuilder {
+    /// The version of the ISF specification, e.g. `"2.0"`.
+    pub fn isfvsn(mut self, isfvsn: impl Into<String>) -> Self {
+        self.isf.isfvsn = Some(isfvsn.into());
+        self
+    }
+
+    /// The version of the shader itself.
+    pub fn vsn(mut self, vsn: impl Into<String>) -> Self {
+        self.isf.vsn = Some(vsn.into());
+        self
+    }
+
+    /// A description of the shader.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.isf.description = Some(description.into());
+        self
+    }
+
+    /// The shader's display name.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.isf.label = Some(label.into());
+        self
+    }
+
+    /// Credit for the author(s) of the shader.
+    pub fn credit(mut self, credit: impl Into<String>) -> Self {
+        self.isf.credit = Some(credit.into());
+        self
+    }
+
+    /// Append a category.
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.isf.categories.push(category.into());
+        self
+    }
+
+    /// Append an input.
+    pub fn input(mut self, input: impl Into<Input>) -> Self {
+        self.isf.inputs.push(input.into());
+        self
+    }
+
+    /// Append a pass.
+    pub fn pass(mut self, pass: impl Into<Pass>) -> Self {
+        self.isf.passes.push(pass.into());
+        self
+    }
+
+    /// Import the image at the given path under the given name, replacing any existing import of
+    /// the same name.
+    pub fn import(mut self, name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        let import = ImageImport { path: path.into() };
+        self.isf.imported.insert(name.into(), import);
+        self
+    }
+
+    /// Produce the `Isf`.
+    pub fn build(self) -> Isf {
+        self.isf
+    }
+}
+
+impl From<IsfBuilder> for Isf {
+    fn from(builder: IsfBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl Input {
+    /// Begin building an input with the given name and type.
+    ///
+    /// The type may also be given as the values of the type, e.g. an `InputFloat`.
+    pub fn builder(name: impl Into<String>, ty: impl Into<InputType>) -> InputBuilder {
+        InputBuilder {
+            input: Input {
+                name: name.into(),
+                label: None,
+                help: None,
+                ty: ty.into(),
+                extra: BTreeMap::new(),
+            },
+        }
+    }
+}
+
+impl InputBuilder {
+    /// Replace the type of the input.
+    pub fn ty(mut self, ty: impl Into<InputType>) -> Self {
+        self.input.ty = ty.into();
+        self
+    }
+
+    /// The label displayed for the input.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.input.label = Some(label.into());
+        self
+    }
+
+    /// Longer help text describing the input.
+    pub fn help(mut self, help: impl Into<String>) -> Self {
+        self.input.help = Some(help.into());
+        self
+    }
+
+    /// Produce the `Input`.
+    pub fn build(self) -> Input {
+        self.input
+    }
+}
+
+impl From<InputBuilder> for Input {
+    fn from(builder: InputBuilder) -> Self {
+        builder.build()
+    }
+}
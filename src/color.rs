@@ -0,0 +1,209 @@
+//! A typed representation of ISF color values.
+
+use crate::InputValue;
+#[cfg(feature = "serde")]
+use serde::de::{self, SeqAccess, Visitor};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "serde")]
+use std::fmt;
+use thiserror::Error;
+
+/// An RGBA color with normalized components.
+///
+/// ISF colors are written as arrays of either three (RGB) or four (RGBA) components, or by
+/// some tools as a `"#rrggbb"` or `"#rrggbbaa"` hex string. When deserializing a `Color`, a
+/// missing alpha defaults to `1.0` and any other number of components is an error. A `Color` is
+/// always serialized as an array of four components.
+///
+/// An [**InputColor**](./struct.InputColor.html) records whether its values were written with
+/// three components via its [**rgb**](./struct.InputColor.html#structfield.rgb) field.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+/// Returned when a color does not have three (RGB) or four (RGBA) components.
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error("expected a color with 3 (RGB) or 4 (RGBA) components, found {components}")]
+pub struct ColorError {
+    /// The number of components found.
+    pub components: usize,
+}
+
+/// Returned when a hex color string such as `"#ff8800"` is malformed.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum HexColorError {
+    #[error("hex color \"{hex}\" must begin with `#`")]
+    MissingHash { hex: String },
+    #[error("hex color \"{hex}\" must have 6 (RGB) or 8 (RGBA) digits")]
+    InvalidLength { hex: String },
+    #[error("hex color \"{hex}\" contains the non-hex digit `{ch}`")]
+    InvalidDigit { hex: String, ch: char },
+}
+
+/// The components of a color value written as either an array of numbers or a hex string.
+///
+/// Used to deserialize the values of a `color` input.
+#[cfg(feature = "serde")]
+pub(crate) struct ColorComponents(pub Vec<f32>);
+
+/// Normalize the components of a color to RGBA, e.g. for uploading to a `vec4` uniform.
+///
+/// Three components are given an alpha of `1.0`. Any other number of components besides three
+/// or four produces an error.
+pub fn color_to_rgba(components: &[f32]) -> Result<[f32; 4], ColorError> {
+    match *components {
+        [r, g, b] => Ok([r, g, b, 1.0]),
+        [r, g, b, a] => Ok([r, g, b, a]),
+        _ => Err(ColorError {
+            components: components.len(),
+        }),
+    }
+}
+
+impl Color {
+    /// A color with the given components.
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// An opaque color with the given components.
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Color::new(r, g, b, 1.0)
+    }
+
+    /// The components in RGBA order.
+    pub fn to_array(&self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    /// Parse a color from a `"#rrggbb"` or `"#rrggbbaa"` hex string.
+    pub fn from_hex(hex: &str) -> Result<Self, HexColorError> {
+        let components = hex_components(hex)?;
+        Ok(Color::from_components(&components).expect("hex colors have 3 or 4 components"))
+    }
+
+    /// A color from three (RGB) or four (RGBA) components.
+    ///
+    /// Returns `None` for any other number of components.
+    pub fn from_components(components: &[f32]) -> Option<Self> {
+        color_to_rgba(components).ok().map(Color::from)
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from([r, g, b, a]: [f32; 4]) -> Self {
+        Color::new(r, g, b, a)
+    }
+}
+
+impl From<[f32; 3]> for Color {
+    fn from([r, g, b]: [f32; 3]) -> Self {
+        Color::rgb(r, g, b)
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> Self {
+        color.to_array()
+    }
+}
+
+impl From<Color> for Vec<f32> {
+    fn from(color: Color) -> Self {
+        color.to_array().to_vec()
+    }
+}
+
+impl From<Color> for InputValue {
+    fn from(color: Color) -> Self {
+        InputValue::Color(color.to_array())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Color {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_array().serialize(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ColorComponents(components) = ColorComponents::deserialize(d)?;
+        let rgba = color_to_rgba(&components).map_err(de::Error::custom)?;
+        Ok(Color::from(rgba))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ColorComponents {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ComponentsVisitor;
+
+        impl<'de> Visitor<'de> for ComponentsVisitor {
+            type Value = ColorComponents;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "an array of color components or a hex color string")
+            }
+
+            fn visit_str<E>(self, hex: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                hex_components(hex).map(ColorComponents).map_err(E::custom)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut components = vec![];
+                while let Some(c) = seq.next_element()? {
+                    components.push(c);
+                }
+                Ok(ColorComponents(components))
+            }
+        }
+
+        d.deserialize_any(ComponentsVisitor)
+    }
+}
+
+/// Parse the normalized components of a `"#rrggbb"` or `"#rrggbbaa"` hex string.
+fn hex_components(hex: &str) -> Result<Vec<f32>, HexColorError> {
+    let digits = hex
+        .strip_prefix('#')
+        .ok_or_else(|| HexColorError::MissingHash {
+            hex: hex.to_string(),
+        })?;
+    if let Some(ch) = digits.chars().find(|ch| !ch.is_ascii_hexdigit()) {
+        let hex = hex.to_string();
+        return Err(HexColorError::InvalidDigit { hex, ch });
+    }
+    if digits.len() != 6 && digits.len() != 8 {
+        let hex = hex.to_string();
+        return Err(HexColorError::InvalidLength { hex });
+    }
+    let components = (0..digits.len())
+        .step_by(2)
+        .map(|ix| u8::from_str_radix(&digits[ix..ix + 2], 16).unwrap())
+        .map(|byte| f32::from(byte) / 255.0)
+        .collect();
+    Ok(components)
+}
@@ -0,0 +1,227 @@
+//! Preservation of `//` comments written within the ISF JSON blob.
+//!
+//! JSON has no notion of comments, however shader authors occasionally annotate the top-level dict
+//! with trailing `//` comments. [**parse_with_comments**](./fn.parse_with_comments.html) strips
+//! these before deserializing and returns them in a [**JsonComments**](./struct.JsonComments.html)
+//! sidecar, which may later be passed to
+//! [**to_string_pretty_with_comments**](./fn.to_string_pretty_with_comments.html) to re-emit them.
+
+use crate::{top_comment_contents, Isf, ParseError};
+use std::collections::BTreeMap;
+
+/// Trailing `//` comments captured from the lines of an ISF JSON blob.
+///
+/// Each comment is anchored to the JSON value that begins on the same line, identified by its
+/// [JSON pointer](https://tools.ietf.org/html/rfc6901), e.g. `/INPUTS/0/DEFAULT`. This allows the
+/// comments to be re-emitted after the `Isf` has been edited and re-serialized, even if the
+/// formatting of the JSON changes.
+///
+/// Only comments that trail JSON on the same line are captured. Comments occupying a line of
+/// their own are discarded.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JsonComments {
+    pub comments: BTreeMap<CommentAnchor, String>,
+}
+
+/// Identifies the location of a comment within the JSON.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CommentAnchor {
+    /// The JSON pointer of the value with which the comment is associated.
+    pub pointer: String,
+    /// Whether the comment trails the line closing the value's object or array rather than the
+    /// line on which the value begins.
+    pub end: bool,
+}
+
+/// A JSON line with its trailing comment removed.
+struct Line<'a> {
+    json: &'a str,
+    anchor: Option<CommentAnchor>,
+    comment: Option<&'a str>,
+}
+
+/// Tracks the containers that enclose the current position within the JSON.
+enum Frame {
+    Object {
+        key: Option<String>,
+        expect_key: bool,
+    },
+    Array {
+        index: usize,
+    },
+}
+
+impl JsonComments {
+    /// Whether or not any comments were captured.
+    pub fn is_empty(&self) -> bool {
+        self.comments.is_empty()
+    }
+
+    /// The comment associated with the value at the given JSON pointer, if any.
+    pub fn get(&self, pointer: &str) -> Option<&str> {
+        let anchor = CommentAnchor {
+            pointer: pointer.to_string(),
+            end: false,
+        };
+        self.comments.get(&anchor).map(|s| &s[..])
+    }
+}
+
+/// Attempt to parse an ISF blob from a GLSL source string, capturing any trailing `//` comments
+/// within the JSON.
+///
+/// This is the same as [**parse**](./fn.parse.html), but tolerates `//` comments within the JSON
+/// and returns them alongside the `Isf`.
+pub fn parse_with_comments(glsl_src: &str) -> Result<(Isf, JsonComments), ParseError> {
+    let comment_contents = top_comment_contents(glsl_src).ok_or(ParseError::MissingTopComment)?;
+    let mut json = String::with_capacity(comment_contents.len());
+    let mut comments = JsonComments::default();
+    for line in scan_lines(comment_contents) {
+        json.push_str(line.json);
+        json.push('\n');
+        if let (Some(anchor), Some(comment)) = (line.anchor, line.comment) {
+            comments.comments.insert(anchor, comment.to_string());
+        }
+    }
+    // Only trailing comments are removed, so positions within the JSON are unchanged.
+    let isf = serde_json::from_str(&json)
+        .map_err(|err| ParseError::json(err, glsl_src, comment_contents))?;
+    Ok((isf, comments))
+}
+
+/// Serialize the `Isf` to a pretty-printed JSON string, re-emitting the given comments.
+///
+/// Comments whose anchors no longer exist within the `Isf` (e.g. because the value was removed)
+/// are dropped. The resulting string may be parsed with
+/// [**parse_with_comments**](./fn.parse_with_comments.html) once placed within the top comment of
+/// a GLSL source string.
+pub fn to_string_pretty_with_comments(
+    isf: &Isf,
+    comments: &JsonComments,
+) -> Result<String, serde_json::Error> {
+    let json = serde_json::to_string_pretty(isf)?;
+    let mut out = String::with_capacity(json.len());
+    for (ix, line) in scan_lines(&json).enumerate() {
+        if ix > 0 {
+            out.push('\n');
+        }
+        out.push_str(line.json);
+        if let Some(comment) = line.anchor.and_then(|a| comments.comments.get(&a)) {
+            out.push_str(" // ");
+            out.push_str(comment);
+        }
+    }
+    Ok(out)
+}
+
+/// Scan each line of the JSON, separating the JSON from any trailing comment and determining the
+/// anchor for the line.
+fn scan_lines(json: &str) -> impl Iterator<Item = Line<'_>> {
+    let mut stack: Vec<Frame> = vec![];
+    json.lines().map(move |line| scan_line(line, &mut stack))
+}
+
+fn scan_line<'a>(line: &'a str, stack: &mut Vec<Frame>) -> Line<'a> {
+    let mut anchor = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((ix, ch)) = chars.next() {
+        // Values beginning within an array are anchored by their index.
+        let value_start = matches!(ch, '{' | '[' | '"' | '-' | '0'..='9' | 't' | 'f' | 'n');
+        if value_start && anchor.is_none() {
+            if let Some(Frame::Array { .. }) = stack.last() {
+                anchor = Some(CommentAnchor {
+                    pointer: pointer(stack),
+                    end: false,
+                });
+            }
+        }
+        match ch {
+            '"' => {
+                let start = ix;
+                let mut end = line.len();
+                let mut escaped = false;
+                for (jx, c) in chars.by_ref() {
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => {
+                            end = jx + 1;
+                            break;
+                        }
+                        _ => (),
+                    }
+                }
+                if let Some(Frame::Object { key, expect_key }) = stack.last_mut() {
+                    if *expect_key {
+                        let raw = &line[start..end];
+                        *key = Some(serde_json::from_str(raw).unwrap_or_else(|_| raw.to_string()));
+                        *expect_key = false;
+                        if anchor.is_none() {
+                            anchor = Some(CommentAnchor {
+                                pointer: pointer(stack),
+                                end: false,
+                            });
+                        }
+                    }
+                }
+            }
+            '{' => stack.push(Frame::Object {
+                key: None,
+                expect_key: true,
+            }),
+            '[' => stack.push(Frame::Array { index: 0 }),
+            '}' | ']' => {
+                stack.pop();
+                if anchor.is_none() {
+                    anchor = Some(CommentAnchor {
+                        pointer: pointer(stack),
+                        end: true,
+                    });
+                }
+            }
+            ',' => match stack.last_mut() {
+                Some(Frame::Object { expect_key, .. }) => *expect_key = true,
+                Some(Frame::Array { index }) => *index += 1,
+                None => (),
+            },
+            '/' if chars.peek().map(|&(_, c)| c) == Some('/') => {
+                let comment = line[ix + 2..].trim();
+                let comment = if comment.is_empty() {
+                    None
+                } else {
+                    Some(comment)
+                };
+                return Line {
+                    json: line[..ix].trim_end(),
+                    anchor,
+                    comment,
+                };
+            }
+            _ => (),
+        }
+    }
+    Line {
+        json: line,
+        anchor,
+        comment: None,
+    }
+}
+
+/// Produce the JSON pointer for the current position described by the stack.
+fn pointer(stack: &[Frame]) -> String {
+    let mut pointer = String::new();
+    for frame in stack {
+        match frame {
+            Frame::Object { key: Some(key), .. } => {
+                pointer.push('/');
+                pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+            }
+            Frame::Object { key: None, .. } => (),
+            Frame::Array { index } => {
+                pointer.push('/');
+                pointer.push_str(&index.to_string());
+            }
+        }
+    }
+    pointer
+}
@@ -0,0 +1,172 @@
+//! Analysis of the dependencies between the passes of an ISF shader.
+
+use crate::{glsl, Isf, Pass};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use thiserror::Error;
+
+/// Returned by [**Isf::execution_order**](./struct.Isf.html#method.execution_order) when the
+/// passes depend upon one another in a cycle.
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error("the dependencies between passes {passes:?} form a cycle")]
+pub struct CycleError {
+    /// The indices of the passes that could not be ordered.
+    pub passes: Vec<usize>,
+}
+
+impl Isf {
+    /// Determine the passes that each pass depends upon.
+    ///
+    /// The returned `Vec` contains an entry for each pass. Each entry lists the indices of the
+    /// passes whose `TARGET` is referenced by the code executed for that pass. A pass is never
+    /// listed as depending upon itself.
+    ///
+    /// The body is the GLSL source that follows the ISF comment. See
+    /// [**glsl::pass_source**](./glsl/fn.pass_source.html) for details on how the code executed for
+    /// each pass is determined.
+    pub fn pass_dependencies(&self, body: &str) -> Vec<Vec<usize>> {
+        (0..self.passes.len())
+            .map(|ix| {
+                let idents = glsl::identifiers(&glsl::pass_source(body, ix));
+                self.passes
+                    .iter()
+                    .enumerate()
+                    .filter(|&(jx, _)| jx != ix)
+                    .filter_map(|(jx, pass)| pass.target.as_ref().map(|t| (jx, t)))
+                    .filter(|(_, target)| idents.contains(&target[..]))
+                    .map(|(jx, _)| jx)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Find pairs of adjacent passes that could be fused into a single pass.
+    ///
+    /// A pair `(a, a + 1)` is considered fusable if both passes render at the same resolution
+    /// with the same `FLOAT` precision and the second pass does not read the output of the first.
+    ///
+    /// This is only an optimisation hint. It is up to the renderer to decide whether or not fusing
+    /// the passes is worthwhile (e.g. by rendering to multiple targets).
+    pub fn fusable_passes(&self, body: &str) -> Vec<(usize, usize)> {
+        let deps = self.pass_dependencies(body);
+        self.passes
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| {
+                let (a, b) = (&pair[0], &pair[1]);
+                a.width == b.width && a.height == b.height && a.float == b.float
+            })
+            .map(|(ix, _)| (ix, ix + 1))
+            .filter(|&(a, b)| !deps[b].contains(&a))
+            .collect()
+    }
+
+    /// Find the passes whose `TARGET` is never read, making them dead work.
+    ///
+    /// A pass is considered orphaned if no subsequent pass references its `TARGET` within the
+    /// code executed for that pass. As the contents of a `PERSISTENT` target are retained for the
+    /// next frame, a reference from any pass (including itself) keeps a persistent pass alive.
+    ///
+    /// Passes without a `TARGET` and the final pass (which renders the output) are never
+    /// reported.
+    pub fn orphan_passes(&self, body: &str) -> Vec<usize> {
+        let deps = self.pass_dependencies(body);
+        let last = self.passes.len().saturating_sub(1);
+        self.passes
+            .iter()
+            .enumerate()
+            .filter(|&(ix, pass)| ix != last && pass.target.is_some())
+            .filter(|&(ix, pass)| {
+                let read_by = |jx: usize| deps[jx].contains(&ix);
+                if !pass.persistent {
+                    return !(ix + 1..deps.len()).any(read_by);
+                }
+                let target = pass.target.as_ref().unwrap();
+                let reads_self = glsl::identifiers(&glsl::pass_source(body, ix)).contains(target);
+                !reads_self && !(0..deps.len()).any(read_by)
+            })
+            .map(|(ix, _)| ix)
+            .collect()
+    }
+
+    /// Produce a single-pass copy of the shader that renders only the pass at the given index to
+    /// the screen, e.g. for previewing the contents of an intermediate buffer.
+    ///
+    /// The pass's `TARGET`, `PERSISTENT`, `WIDTH` and `HEIGHT` are cleared so that it renders
+    /// directly to the output at the render size. All inputs and imports are retained as-is, as
+    /// the code executed for the pass may refer to any of them.
+    ///
+    /// Note that the shader body will see a `PASSINDEX` of `0` when rendering the isolated pass.
+    /// Hosts should provide the original index instead where the body branches on `PASSINDEX`.
+    ///
+    /// Returns `None` if there is no pass at the given index.
+    pub fn isolate_pass(&self, index: usize) -> Option<Isf> {
+        let pass = self.passes.get(index)?;
+        let pass = Pass {
+            target: None,
+            persistent: false,
+            width: None,
+            height: None,
+            width_is_number: false,
+            height_is_number: false,
+            ..pass.clone()
+        };
+        let mut isf = self.clone();
+        isf.passes = vec![pass];
+        Some(isf)
+    }
+
+    /// Determine an order in which the passes may be executed such that every pass runs after the
+    /// passes whose targets it reads.
+    ///
+    /// Passes are kept in their authored order where the dependencies allow. A pass reading a
+    /// `PERSISTENT` target that is written by a later pass is considered to read the previous
+    /// frame's contents, so this does not constrain the order.
+    ///
+    /// A shader without any `PASSES` is implicitly a single pass, in which case `[0]` is returned.
+    pub fn execution_order(&self, body: &str) -> Result<Vec<usize>, CycleError> {
+        if self.passes.is_empty() {
+            return Ok(vec![0]);
+        }
+        let deps: Vec<Vec<usize>> = self
+            .pass_dependencies(body)
+            .into_iter()
+            .enumerate()
+            .map(|(ix, deps)| {
+                deps.into_iter()
+                    .filter(|&dep| dep < ix || !self.passes[dep].persistent)
+                    .collect()
+            })
+            .collect();
+        topological_order(&deps)
+    }
+}
+
+/// Order the nodes of a graph such that every node follows the nodes it depends upon, where
+/// `deps[n]` lists the nodes upon which node `n` depends.
+///
+/// Nodes are kept in ascending order where the dependencies allow.
+pub(crate) fn topological_order(deps: &[Vec<usize>]) -> Result<Vec<usize>, CycleError> {
+    let mut in_degree: Vec<usize> = deps.iter().map(Vec::len).collect();
+    let mut ready: BinaryHeap<_> = (0..deps.len())
+        .filter(|&ix| in_degree[ix] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(deps.len());
+    while let Some(Reverse(ix)) = ready.pop() {
+        order.push(ix);
+        for (jx, pass_deps) in deps.iter().enumerate() {
+            for _ in pass_deps.iter().filter(|&&dep| dep == ix) {
+                in_degree[jx] -= 1;
+                if in_degree[jx] == 0 {
+                    ready.push(Reverse(jx));
+                }
+            }
+        }
+    }
+    if order.len() < deps.len() {
+        let passes = (0..deps.len()).filter(|&ix| in_degree[ix] > 0).collect();
+        return Err(CycleError { passes });
+    }
+    Ok(order)
+}
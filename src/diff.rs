@@ -0,0 +1,116 @@
+//! Comparison of the interfaces exposed by two ISF shaders.
+
+use crate::{Input, InputType, Isf};
+use std::collections::BTreeMap;
+use std::mem;
+
+/// The difference between the input interfaces of two shaders.
+///
+/// Only changes to the set of input names and their types are reported. Changes that only affect
+/// an input's values (e.g. `DEFAULT`, `MIN`, `MAX`, `VALUES` or `LABEL`) are ignored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterfaceDiff {
+    /// Inputs present in the other shader but not in this one.
+    pub added: Vec<String>,
+    /// Inputs present in this shader but not in the other one.
+    pub removed: Vec<String>,
+    /// Inputs present in both shaders but with a different `TYPE`.
+    pub retyped: Vec<String>,
+}
+
+/// Describes whether a value bound to an input may be carried over to a newer revision of a
+/// shader.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Carryover {
+    /// The input has the same name, type and range, so its value may be carried over as-is.
+    Unchanged,
+    /// The input has the same name and type but its range (`MIN`, `MAX` or `VALUES`) changed, so
+    /// a carried over value may need clamping or validating.
+    RangeChanged,
+    /// The input has the same name but a different type, so its value cannot be carried over.
+    Retyped,
+    /// The input only exists within the older revision.
+    Removed,
+    /// The input only exists within the newer revision.
+    Added,
+}
+
+impl InterfaceDiff {
+    /// Whether or not the interfaces are identical.
+    ///
+    /// If so, values bound to the inputs of one shader may be carried over to the other.
+    pub fn is_unchanged(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.retyped.is_empty()
+    }
+}
+
+impl Isf {
+    /// Compare the input interface of this shader with that of `other`.
+    ///
+    /// Inputs are matched by name. See [**InterfaceDiff**](./struct.InterfaceDiff.html) for
+    /// details on what is considered a change.
+    pub fn interface_diff(&self, other: &Isf) -> InterfaceDiff {
+        let mut diff = InterfaceDiff::default();
+        for input in &self.inputs {
+            match other.input(&input.name) {
+                None => diff.removed.push(input.name.clone()),
+                Some(o) if !o.same_signature(input) => {
+                    diff.retyped.push(input.name.clone());
+                }
+                Some(_) => (),
+            }
+        }
+        for input in &other.inputs {
+            if self.input(&input.name).is_none() {
+                diff.added.push(input.name.clone());
+            }
+        }
+        diff
+    }
+
+    /// Classify each input by whether its value may be carried over to the `newer` revision of
+    /// the shader, e.g. when migrating a user's saved preset.
+    ///
+    /// Inputs are matched by name. The map contains an entry for every input of either shader.
+    /// Changes to an input's `DEFAULT`, `IDENTITY` or labels do not affect the classification.
+    pub fn carryover_map(&self, newer: &Isf) -> BTreeMap<String, Carryover> {
+        let mut map = BTreeMap::new();
+        for input in &self.inputs {
+            let carryover = match newer.input(&input.name) {
+                None => Carryover::Removed,
+                Some(n) if !input.same_signature(n) => Carryover::Retyped,
+                Some(n) if !same_range(&input.ty, &n.ty) => Carryover::RangeChanged,
+                Some(_) => Carryover::Unchanged,
+            };
+            map.insert(input.name.clone(), carryover);
+        }
+        for input in &newer.inputs {
+            map.entry(input.name.clone()).or_insert(Carryover::Added);
+        }
+        map
+    }
+}
+
+impl Input {
+    /// Whether or not the inputs have the same name and `TYPE`.
+    ///
+    /// If so, a value bound to one input is of the correct type for the other.
+    pub fn same_signature(&self, other: &Input) -> bool {
+        self.name == other.name && mem::discriminant(&self.ty) == mem::discriminant(&other.ty)
+    }
+}
+
+/// Whether or not the two input types describe the same range of valid values.
+fn same_range(a: &InputType, b: &InputType) -> bool {
+    match (a, b) {
+        (InputType::Long(a), InputType::Long(b)) => {
+            a.min == b.min && a.max == b.max && a.values == b.values
+        }
+        (InputType::Float(a), InputType::Float(b)) => a.min == b.min && a.max == b.max,
+        (InputType::Point2d(a), InputType::Point2d(b)) => a.min == b.min && a.max == b.max,
+        (InputType::Color(a), InputType::Color(b)) => a.min == b.min && a.max == b.max,
+        (InputType::Audio(a), InputType::Audio(b)) => a.num_samples == b.num_samples,
+        (InputType::AudioFft(a), InputType::AudioFft(b)) => a.num_columns == b.num_columns,
+        _ => true,
+    }
+}
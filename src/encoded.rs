@@ -0,0 +1,43 @@
+//! Support for ISF blobs stored as base64 encoded data URIs.
+//!
+//! Some exporters store the ISF JSON base64 encoded within the top comment, avoiding the need to
+//! escape any `*/` sequences within the JSON. The encoded blob is stored as a data URI, e.g.
+//!
+//! ```glsl
+//! /*data:application/json;base64,eyJJTlBVVFMiOltdfQ==*/
+//! ```
+
+use crate::{top_comment_contents, Isf, ParseError};
+use base64::Engine;
+
+/// The prefix identifying a base64 encoded ISF blob within the top comment.
+pub const DATA_URI_PREFIX: &str = "data:application/json;base64,";
+
+/// Attempt to parse an ISF blob from a GLSL source string, decoding the blob first if it is
+/// stored as a base64 data URI.
+///
+/// Blobs stored as plain JSON are parsed as they would be by [**parse**](./fn.parse.html).
+pub fn parse_encoded(glsl_src: &str) -> Result<Isf, ParseError> {
+    let comment_contents = top_comment_contents(glsl_src).ok_or(ParseError::MissingTopComment)?;
+    match comment_contents.strip_prefix(DATA_URI_PREFIX) {
+        None => serde_json::from_str(comment_contents)
+            .map_err(|err| ParseError::json(err, glsl_src, comment_contents)),
+        Some(encoded) => {
+            let json = base64::engine::general_purpose::STANDARD
+                .decode(encoded.trim())
+                .map_err(|err| ParseError::Base64 { err })?;
+            // The decoded JSON has no position within the source, so report the blob's start.
+            serde_json::from_slice(&json)
+                .map_err(|err| ParseError::json_at_start(err, glsl_src, encoded))
+        }
+    }
+}
+
+/// Serialize the `Isf` to a `/* */` comment containing the JSON as a base64 data URI.
+///
+/// The result may be parsed with [**parse_encoded**](./fn.parse_encoded.html).
+pub fn to_encoded_comment(isf: &Isf) -> Result<String, serde_json::Error> {
+    let json = serde_json::to_vec(isf)?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(json);
+    Ok(format!("/*{}{}*/", DATA_URI_PREFIX, encoded))
+}
@@ -0,0 +1,573 @@
+//! Parsing and evaluation of the `WIDTH` and `HEIGHT` expressions of ISF passes.
+//!
+//! ISF allows the dimensions of a pass's target to be described by an expression in terms of the
+//! render size (`$WIDTH` and `$HEIGHT`) and the values of the shader's inputs (`$inputName`), e.g.
+//! `"floor($WIDTH / 2.0)"`. In addition to the spec, the size of another pass's target may be
+//! referenced via `$targetName.width` and `$targetName.height`.
+
+use crate::deps::topological_order;
+use crate::{CycleError, InputType, InputValue, Isf, Pass};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use thiserror::Error;
+
+/// A parsed `WIDTH` or `HEIGHT` expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DimExpr {
+    Num(f32),
+    /// A variable reference with the leading `$` removed, e.g. `WIDTH` or `blur.width`.
+    Var(String),
+    Neg(Box<DimExpr>),
+    Binary(BinOp, Box<DimExpr>, Box<DimExpr>),
+    Call(Func, Vec<DimExpr>),
+}
+
+/// The binary operators supported within a `DimExpr`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// The functions supported within a `DimExpr`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Func {
+    Floor,
+    Ceil,
+    Max,
+    Min,
+}
+
+/// Errors that might occur while parsing a `DimExpr`.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum ExprError {
+    #[error("unexpected character `{ch}` at byte {pos}")]
+    UnexpectedChar { pos: usize, ch: char },
+    #[error("unexpected token at byte {pos}")]
+    UnexpectedToken { pos: usize },
+    #[error("unexpected end of expression")]
+    UnexpectedEnd,
+    #[error("invalid number `{text}`")]
+    InvalidNumber { text: String },
+    #[error("unknown function `{name}`")]
+    UnknownFunction { name: String },
+    #[error("`{}` expects {expected} argument(s) but {found} were given", func.name())]
+    ArgCount {
+        func: Func,
+        expected: usize,
+        found: usize,
+    },
+}
+
+/// Errors that might occur while evaluating a `DimExpr`.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum EvalError {
+    #[error("the variable `${name}` is not defined")]
+    UndefinedVariable { name: String },
+    #[error("the input `${name}` does not have a scalar value")]
+    NonScalarInput { name: String },
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("`{}` expects {expected} argument(s) but {found} were given", func.name())]
+    ArgCount {
+        func: Func,
+        expected: usize,
+        found: usize,
+    },
+}
+
+/// Errors that might occur while resolving the size of each pass.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum PassSizeError {
+    #[error("failed to parse a dimension of pass {pass}: {err}")]
+    Expr {
+        pass: usize,
+        #[source]
+        err: ExprError,
+    },
+    #[error("failed to evaluate a dimension of pass {pass}: {err}")]
+    Eval {
+        pass: usize,
+        #[source]
+        err: EvalError,
+    },
+    #[error("the sizes of the passes depend upon one another: {err}")]
+    Cycle {
+        #[from]
+        err: CycleError,
+    },
+}
+
+/// The variables available to a `DimExpr` during evaluation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DimContext {
+    /// The value of `$WIDTH`, i.e. the width of the render target in pixels.
+    pub width: f32,
+    /// The value of `$HEIGHT`, i.e. the height of the render target in pixels.
+    pub height: f32,
+    /// The values of the shader's inputs by name.
+    ///
+    /// `bool` and `event` values evaluate to `0.0` or `1.0`. `point2D` and `color` values are not
+    /// scalars and cannot be referenced.
+    pub inputs: BTreeMap<String, InputValue>,
+    /// The resolved size of each pass target by name, referenced as `$name.width` and
+    /// `$name.height`.
+    pub targets: BTreeMap<String, [f32; 2]>,
+}
+
+impl Func {
+    /// The name of the function as written within an expression.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Func::Floor => "floor",
+            Func::Ceil => "ceil",
+            Func::Max => "max",
+            Func::Min => "min",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        let func = match name {
+            "floor" => Func::Floor,
+            "ceil" => Func::Ceil,
+            "max" => Func::Max,
+            "min" => Func::Min,
+            _ => return None,
+        };
+        Some(func)
+    }
+
+    /// The number of arguments expected by the function.
+    pub fn arity(&self) -> usize {
+        match *self {
+            Func::Floor | Func::Ceil => 1,
+            Func::Max | Func::Min => 2,
+        }
+    }
+}
+
+impl DimContext {
+    /// A context with the given render size and no inputs or targets.
+    pub fn new(width: f32, height: f32) -> Self {
+        DimContext {
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+
+    /// Add the value of an input.
+    pub fn with_input(mut self, name: impl Into<String>, value: InputValue) -> Self {
+        self.inputs.insert(name.into(), value);
+        self
+    }
+
+    /// Add the `DEFAULT` of each of the shader's scalar inputs (`float`, `long` and `bool`) that
+    /// does not already have a value.
+    ///
+    /// This allows passes to be sized before the host has produced any input values of its own.
+    pub fn with_defaults(mut self, isf: &Isf) -> Self {
+        for input in &isf.inputs {
+            let value = match input.ty {
+                InputType::Float(ref f) => f.default.map(InputValue::Float),
+                InputType::Long(ref l) => l.default.map(InputValue::Long),
+                InputType::Bool(ref b) => b.default.map(InputValue::Bool),
+                _ => None,
+            };
+            if let Some(value) = value {
+                self.inputs.entry(input.name.clone()).or_insert(value);
+            }
+        }
+        self
+    }
+
+    /// Look up the value of the variable with the given name (excluding the leading `$`).
+    pub fn var(&self, name: &str) -> Result<f32, EvalError> {
+        let undefined = || EvalError::UndefinedVariable {
+            name: name.to_string(),
+        };
+        match name {
+            "WIDTH" => return Ok(self.width),
+            "HEIGHT" => return Ok(self.height),
+            _ => (),
+        }
+        if let Some((target, dim)) = target_dim(name) {
+            let size = self.targets.get(target).ok_or_else(undefined)?;
+            return Ok(size[dim]);
+        }
+        match *self.inputs.get(name).ok_or_else(undefined)? {
+            InputValue::Float(f) => Ok(f),
+            InputValue::Long(l) => Ok(l as f32),
+            InputValue::Bool(b) | InputValue::Event(b) => Ok(b as u32 as f32),
+            _ => Err(EvalError::NonScalarInput {
+                name: name.to_string(),
+            }),
+        }
+    }
+}
+
+impl DimExpr {
+    /// Parse an expression, e.g. `"max(floor($WIDTH * 0.5), 1.0)"`.
+    pub fn parse(src: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser {
+            tokens: lex(src)?,
+            pos: 0,
+        };
+        let expr = parser.expr()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some(&(pos, _)) => Err(ExprError::UnexpectedToken { pos }),
+        }
+    }
+
+    /// Evaluate the expression within the given context.
+    pub fn eval(&self, ctx: &DimContext) -> Result<f32, EvalError> {
+        match *self {
+            DimExpr::Num(n) => Ok(n),
+            DimExpr::Var(ref name) => ctx.var(name),
+            DimExpr::Neg(ref e) => Ok(-e.eval(ctx)?),
+            DimExpr::Binary(op, ref l, ref r) => {
+                let (l, r) = (l.eval(ctx)?, r.eval(ctx)?);
+                match op {
+                    BinOp::Add => Ok(l + r),
+                    BinOp::Sub => Ok(l - r),
+                    BinOp::Mul => Ok(l * r),
+                    BinOp::Div if r == 0.0 => Err(EvalError::DivisionByZero),
+                    BinOp::Div => Ok(l / r),
+                }
+            }
+            DimExpr::Call(func, ref args) => {
+                // A `Call` may be constructed directly, so the arity is not guaranteed by parsing.
+                if args.len() != func.arity() {
+                    return Err(EvalError::ArgCount {
+                        func,
+                        expected: func.arity(),
+                        found: args.len(),
+                    });
+                }
+                let args = args
+                    .iter()
+                    .map(|a| a.eval(ctx))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match func {
+                    Func::Floor => Ok(args[0].floor()),
+                    Func::Ceil => Ok(args[0].ceil()),
+                    Func::Max => Ok(args[0].max(args[1])),
+                    Func::Min => Ok(args[0].min(args[1])),
+                }
+            }
+        }
+    }
+
+    /// The names of all variables referenced by the expression, excluding the leading `$`.
+    pub fn variables(&self) -> BTreeSet<&str> {
+        let mut vars = BTreeSet::new();
+        self.visit_vars(&mut |name| {
+            vars.insert(name);
+        });
+        vars
+    }
+
+    fn visit_vars<'a>(&'a self, f: &mut dyn FnMut(&'a str)) {
+        match *self {
+            DimExpr::Num(_) => (),
+            DimExpr::Var(ref name) => f(name),
+            DimExpr::Neg(ref e) => e.visit_vars(f),
+            DimExpr::Binary(_, ref l, ref r) => {
+                l.visit_vars(f);
+                r.visit_vars(f);
+            }
+            DimExpr::Call(_, ref args) => args.iter().for_each(|a| a.visit_vars(f)),
+        }
+    }
+}
+
+impl fmt::Display for DimExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DimExpr::Num(n) => write!(f, "{:?}", n),
+            DimExpr::Var(ref name) => write!(f, "${}", name),
+            DimExpr::Neg(ref e) => write!(f, "-({})", e),
+            DimExpr::Binary(op, ref l, ref r) => {
+                let op = match op {
+                    BinOp::Add => '+',
+                    BinOp::Sub => '-',
+                    BinOp::Mul => '*',
+                    BinOp::Div => '/',
+                };
+                write!(f, "({} {} {})", l, op, r)
+            }
+            DimExpr::Call(func, ref args) => {
+                write!(f, "{}(", func.name())?;
+                for (ix, arg) in args.iter().enumerate() {
+                    if ix > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl Pass {
+    /// Parse the `WIDTH` expression, if any.
+    pub fn width_expr(&self) -> Result<Option<DimExpr>, ExprError> {
+        self.width.as_ref().map(|s| DimExpr::parse(s)).transpose()
+    }
+
+    /// Parse the `HEIGHT` expression, if any.
+    pub fn height_expr(&self) -> Result<Option<DimExpr>, ExprError> {
+        self.height.as_ref().map(|s| DimExpr::parse(s)).transpose()
+    }
+}
+
+impl Isf {
+    /// Resolve the size of each pass's target in pixels.
+    ///
+    /// Passes without a `WIDTH` or `HEIGHT` take the render size described by `ctx`. A pass may
+    /// reference the resolved size of another pass's `TARGET` via `$name.width` and
+    /// `$name.height`, so the passes are evaluated in the order implied by these references
+    /// rather than their authored order. Any `targets` within `ctx` are shadowed by the resolved
+    /// sizes of the passes.
+    ///
+    /// Resolved dimensions are rounded down and are at least `1`. A shader without any `PASSES`
+    /// is implicitly a single pass at the render size.
+    pub fn pass_sizes(&self, ctx: &DimContext) -> Result<Vec<[u32; 2]>, PassSizeError> {
+        if self.passes.is_empty() {
+            return Ok(vec![[to_pixels(ctx.width), to_pixels(ctx.height)]]);
+        }
+        let exprs = self
+            .passes
+            .iter()
+            .enumerate()
+            .map(|(pass, p)| {
+                let w = p
+                    .width_expr()
+                    .map_err(|err| PassSizeError::Expr { pass, err })?;
+                let h = p
+                    .height_expr()
+                    .map_err(|err| PassSizeError::Expr { pass, err })?;
+                Ok([w, h])
+            })
+            .collect::<Result<Vec<_>, PassSizeError>>()?;
+        let deps: Vec<Vec<usize>> = exprs
+            .iter()
+            .map(|dims| {
+                let referenced: BTreeSet<&str> = dims
+                    .iter()
+                    .flatten()
+                    .flat_map(|e| e.variables())
+                    .filter_map(|v| target_dim(v).map(|(target, _)| target))
+                    .collect();
+                let targets = self.passes.iter().map(|p| p.target.as_deref());
+                targets
+                    .enumerate()
+                    .filter(|(_, t)| t.is_some_and(|t| referenced.contains(t)))
+                    .map(|(ix, _)| ix)
+                    .collect()
+            })
+            .collect();
+        let order = topological_order(&deps)?;
+        let mut ctx = ctx.clone();
+        let mut sizes = vec![[0, 0]; self.passes.len()];
+        for pass in order {
+            let mut size = [0; 2];
+            for (dim, expr) in exprs[pass].iter().enumerate() {
+                let value = match *expr {
+                    None => [ctx.width, ctx.height][dim],
+                    Some(ref e) => e
+                        .eval(&ctx)
+                        .map_err(|err| PassSizeError::Eval { pass, err })?,
+                };
+                size[dim] = to_pixels(value);
+            }
+            if let Some(ref target) = self.passes[pass].target {
+                let resolved = [size[0] as f32, size[1] as f32];
+                ctx.targets.insert(target.clone(), resolved);
+            }
+            sizes[pass] = size;
+        }
+        Ok(sizes)
+    }
+}
+
+/// Split a `name.width` or `name.height` variable into the target name and dimension index.
+fn target_dim(var: &str) -> Option<(&str, usize)> {
+    let (target, dim) = var.rsplit_once('.')?;
+    match dim {
+        "width" => Some((target, 0)),
+        "height" => Some((target, 1)),
+        _ => None,
+    }
+}
+
+fn to_pixels(value: f32) -> u32 {
+    value.floor().max(1.0) as u32
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Num(f32),
+    Var(String),
+    Ident(String),
+    Op(char),
+}
+
+fn lex(src: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let mut tokens = vec![];
+    let mut chars = src.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        let mut take_while = |f: &dyn Fn(char) -> bool| {
+            let mut end = start + ch.len_utf8();
+            while let Some(&(ix, c)) = chars.peek() {
+                if !f(c) {
+                    break;
+                }
+                end = ix + c.len_utf8();
+                chars.next();
+            }
+            end
+        };
+        let token = match ch {
+            _ if ch.is_whitespace() => continue,
+            '0'..='9' | '.' => {
+                let end = take_while(&|c| c.is_ascii_digit() || c == '.');
+                let text = &src[start..end];
+                let n = text.parse().map_err(|_| ExprError::InvalidNumber {
+                    text: text.to_string(),
+                })?;
+                Token::Num(n)
+            }
+            '$' => {
+                let end = take_while(&|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+                if end == start + 1 {
+                    return Err(match src[end..].chars().next() {
+                        None => ExprError::UnexpectedEnd,
+                        Some(ch) => ExprError::UnexpectedChar { pos: end, ch },
+                    });
+                }
+                Token::Var(src[start + 1..end].to_string())
+            }
+            _ if ch.is_ascii_alphabetic() || ch == '_' => {
+                let end = take_while(&|c| c.is_ascii_alphanumeric() || c == '_');
+                Token::Ident(src[start..end].to_string())
+            }
+            '+' | '-' | '*' | '/' | '(' | ')' | ',' => Token::Op(ch),
+            _ => return Err(ExprError::UnexpectedChar { pos: start, ch }),
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser over the lexed tokens.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Result<(usize, Token), ExprError> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok.ok_or(ExprError::UnexpectedEnd)
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if let Some((_, Token::Op(c))) = self.tokens.get(self.pos) {
+            if *c == op {
+                self.pos += 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn expect(&mut self, op: char) -> Result<(), ExprError> {
+        match self.next()? {
+            (_, Token::Op(c)) if c == op => Ok(()),
+            (pos, _) => Err(ExprError::UnexpectedToken { pos }),
+        }
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<DimExpr, ExprError> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = if self.eat('+') {
+                BinOp::Add
+            } else if self.eat('-') {
+                BinOp::Sub
+            } else {
+                return Ok(lhs);
+            };
+            lhs = DimExpr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    // term := unary (('*' | '/') unary)*
+    fn term(&mut self) -> Result<DimExpr, ExprError> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                BinOp::Mul
+            } else if self.eat('/') {
+                BinOp::Div
+            } else {
+                return Ok(lhs);
+            };
+            lhs = DimExpr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    // unary := ('-' | '+') unary | primary
+    fn unary(&mut self) -> Result<DimExpr, ExprError> {
+        if self.eat('-') {
+            return Ok(DimExpr::Neg(Box::new(self.unary()?)));
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.primary()
+    }
+
+    // primary := number | variable | ident '(' args ')' | '(' expr ')'
+    fn primary(&mut self) -> Result<DimExpr, ExprError> {
+        match self.next()? {
+            (_, Token::Num(n)) => Ok(DimExpr::Num(n)),
+            (_, Token::Var(name)) => Ok(DimExpr::Var(name)),
+            (_, Token::Op('(')) => {
+                let expr = self.expr()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            (_, Token::Ident(name)) => {
+                let func = Func::from_name(&name).ok_or(ExprError::UnknownFunction { name })?;
+                self.expect('(')?;
+                let mut args = vec![];
+                if !self.eat(')') {
+                    loop {
+                        args.push(self.expr()?);
+                        if self.eat(')') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                if args.len() != func.arity() {
+                    return Err(ExprError::ArgCount {
+                        func,
+                        expected: func.arity(),
+                        found: args.len(),
+                    });
+                }
+                Ok(DimExpr::Call(func, args))
+            }
+            (pos, _) => Err(ExprError::UnexpectedToken { pos }),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Extraction of the ISF JSON blob from sources using alternative comment delimiters.
+
+use crate::{comment_range, Isf, ParseError};
+
+/// Describes how to locate the comment containing the ISF JSON blob within a source string.
+///
+/// The default options locate the first `/* */` comment as described by the spec.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExtractOptions {
+    /// The delimiter opening the comment, e.g. `/*`.
+    pub open: String,
+    /// The delimiter closing the comment, e.g. `*/`.
+    pub close: String,
+}
+
+impl ExtractOptions {
+    /// Locate the first HTML `<!-- -->` comment, e.g. for shaders embedded within an `.html`
+    /// wrapper.
+    pub fn html() -> Self {
+        ExtractOptions {
+            open: "<!--".to_string(),
+            close: "-->".to_string(),
+        }
+    }
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        ExtractOptions {
+            open: "/*".to_string(),
+            close: "*/".to_string(),
+        }
+    }
+}
+
+/// Attempt to parse an ISF blob from the first comment delimited as described by the options.
+///
+/// Comments are located in the same manner as [**parse**](./fn.parse.html): comments that do not
+/// begin with a JSON object (e.g. a license header) are skipped and `options.close` sequences
+/// within JSON strings do not close the comment.
+pub fn parse_with_options(src: &str, options: &ExtractOptions) -> Result<Isf, ParseError> {
+    let range =
+        comment_range(src, &options.open, &options.close).ok_or(ParseError::MissingTopComment)?;
+    let json = src[range].trim();
+    serde_json::from_str(json).map_err(|err| ParseError::json(err, src, json))
+}
+
+/// Attempt to parse an ISF blob from the first `<!-- -->` comment within an HTML document.
+///
+/// This is short-hand for [**parse_with_options**](./fn.parse_with_options.html) with
+/// [**ExtractOptions::html**](./struct.ExtractOptions.html#method.html).
+pub fn parse_html_embedded(html: &str) -> Result<Isf, ParseError> {
+    parse_with_options(html, &ExtractOptions::html())
+}
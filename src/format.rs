@@ -0,0 +1,31 @@
+//! Conversion of an `Isf` to and from formats other than JSON.
+//!
+//! Each format is gated behind a feature of the same name. JSON remains the canonical format for
+//! ISF and is always available.
+
+#[cfg(any(feature = "toml", feature = "yaml"))]
+use crate::Isf;
+
+/// Serialize the `Isf` to a TOML string.
+#[cfg(feature = "toml")]
+pub fn to_toml(isf: &Isf) -> Result<String, toml::ser::Error> {
+    toml::to_string(isf)
+}
+
+/// Deserialize an `Isf` from a TOML string.
+#[cfg(feature = "toml")]
+pub fn from_toml(s: &str) -> Result<Isf, toml::de::Error> {
+    toml::from_str(s)
+}
+
+/// Serialize the `Isf` to a YAML string.
+#[cfg(feature = "yaml")]
+pub fn to_yaml(isf: &Isf) -> Result<String, serde_yaml::Error> {
+    serde_yaml::to_string(isf)
+}
+
+/// Deserialize an `Isf` from a YAML string.
+#[cfg(feature = "yaml")]
+pub fn from_yaml(s: &str) -> Result<Isf, serde_yaml::Error> {
+    serde_yaml::from_str(s)
+}
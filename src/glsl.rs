@@ -0,0 +1,488 @@
+//! Lightweight scanning of GLSL source code.
+//!
+//! None of these functions perform a full GLSL parse. Instead they provide just enough
+//! tokenization to answer questions about an ISF shader body, e.g. which identifiers are
+//! referenced by the code executed for a particular pass.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// A single token within a GLSL source string along with its byte range.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Token<'a> {
+    pub text: &'a str,
+    pub span: Range<usize>,
+}
+
+/// Replace all `//` and `/* */` comments with whitespace.
+///
+/// Newlines are preserved and every other byte within a comment is replaced with a space, so that
+/// byte offsets and line numbers within the returned string match the original source.
+pub fn strip_comments(src: &str) -> String {
+    let bytes = src.as_bytes();
+    let mut out = String::with_capacity(src.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let comment_end = match (bytes[i], bytes.get(i + 1)) {
+            (b'/', Some(b'/')) => src[i..].find('\n').map(|n| i + n).unwrap_or(src.len()),
+            (b'/', Some(b'*')) => src[i + 2..]
+                .find("*/")
+                .map(|n| i + 2 + n + 2)
+                .unwrap_or(src.len()),
+            _ => {
+                let ch = src[i..].chars().next().unwrap();
+                out.push(ch);
+                i += ch.len_utf8();
+                continue;
+            }
+        };
+        out.push_str(&blank(&src[i..comment_end]));
+        i = comment_end;
+    }
+    out
+}
+
+/// Tokenize the given source.
+///
+/// Comments are expected to have already been stripped. Identifiers and numbers are produced as
+/// whole tokens, `==`, `!=`, `<=`, `>=`, `&&` and `||` are produced as two-character tokens and all
+/// other non-whitespace characters are produced as single-character tokens.
+pub(crate) fn tokens(src: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut chars = src.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        if ch.is_whitespace() {
+            continue;
+        }
+        let mut end = start + ch.len_utf8();
+        if is_ident_start(ch) || ch.is_ascii_digit() {
+            while let Some(&(ix, c)) = chars.peek() {
+                let continues = is_ident_continue(c) || (!is_ident_start(ch) && c == '.');
+                if !continues {
+                    break;
+                }
+                end = ix + c.len_utf8();
+                chars.next();
+            }
+        } else if let Some(&(ix, next)) = chars.peek() {
+            let pair = [ch, next];
+            let is_pair = matches!(
+                pair,
+                ['=', '='] | ['!', '='] | ['<', '='] | ['>', '='] | ['&', '&'] | ['|', '|']
+            );
+            if is_pair {
+                end = ix + next.len_utf8();
+                chars.next();
+            }
+        }
+        tokens.push(Token {
+            text: &src[start..end],
+            span: start..end,
+        });
+    }
+    tokens
+}
+
+/// Collect the set of identifiers referenced within the given source, ignoring comments.
+pub fn identifiers(src: &str) -> BTreeSet<String> {
+    let stripped = strip_comments(src);
+    tokens(&stripped)
+        .into_iter()
+        .filter(|t| t.text.starts_with(is_ident_start))
+        .map(|t| t.text.to_string())
+        .collect()
+}
+
+/// Whether or not the given identifier is referenced anywhere within the source.
+///
+/// Only whole identifiers are matched and comments are ignored.
+pub fn references(src: &str, ident: &str) -> bool {
+    let stripped = strip_comments(src);
+    tokens(&stripped).iter().any(|t| t.text == ident)
+}
+
+/// Replace every reference to the identifier `ident` with `replacement`.
+///
+/// Only whole identifiers are replaced. Occurrences within comments and field selections (e.g.
+/// the `x` of `pos.x`) are left untouched.
+pub fn replace_identifier(src: &str, ident: &str, replacement: &str) -> String {
+    let stripped = strip_comments(src);
+    let toks = tokens(&stripped);
+    let mut out = String::with_capacity(src.len());
+    let mut last = 0;
+    for (ix, tok) in toks.iter().enumerate() {
+        let is_field = ix > 0 && toks[ix - 1].text == ".";
+        if tok.text != ident || is_field {
+            continue;
+        }
+        out.push_str(&src[last..tok.span.start]);
+        out.push_str(replacement);
+        last = tok.span.end;
+    }
+    out.push_str(&src[last..]);
+    out
+}
+
+/// Produce the portion of an ISF shader body that is executed for the pass at the given index.
+///
+/// ISF executes the same body for every pass, with the body branching on the `PASSINDEX` uniform.
+/// This function blanks out the statements guarded by `if (PASSINDEX == N)` conditions (along with
+/// their `else` branches) that cannot run for `pass_index`. Comments are stripped and byte offsets
+/// are preserved.
+///
+/// Conditions other than a direct comparison between `PASSINDEX` and an integer literal are left
+/// untouched, so the result is a conservative superset of the code run for the pass.
+pub fn pass_source(body: &str, pass_index: usize) -> String {
+    let stripped = strip_comments(body);
+    let mut blanked = vec![];
+    let toks = tokens(&stripped);
+    for i in 0..toks.len() {
+        if let Some((n, stmt_start)) = passindex_condition(&toks, i) {
+            let stmt_end = statement_end(&toks, stmt_start);
+            if n != pass_index {
+                blanked.push(span(&toks, stmt_start, stmt_end));
+            } else if toks.get(stmt_end).map(|t| t.text) == Some("else") {
+                let else_end = statement_end(&toks, stmt_end + 1);
+                blanked.push(span(&toks, stmt_end, else_end));
+            }
+        }
+    }
+    let mut out = stripped.clone();
+    for range in blanked {
+        out.replace_range(range.clone(), &blank(&stripped[range]));
+    }
+    out
+}
+
+/// A `uniform` declared within GLSL source.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GlslUniform {
+    pub name: String,
+    /// The GLSL type, e.g. `float` or `sampler2D`.
+    pub ty: String,
+    /// The precision qualifier (`lowp`, `mediump` or `highp`), if any.
+    pub precision: Option<String>,
+    /// The array length as written within the brackets (e.g. `4` or `N`) if the uniform is an
+    /// array. This is an empty string for arrays with an unspecified length.
+    pub array_len: Option<String>,
+}
+
+/// Find all `uniform` declarations within the given GLSL source.
+///
+/// Handles `layout(...)` and precision qualifiers, arrays (declared either on the type or the
+/// name) and lines declaring multiple uniforms, e.g. `uniform highp vec2 a, b[4];`. Uniform
+/// blocks, comments and preprocessor directives are ignored.
+pub fn declared_uniforms(src: &str) -> Vec<GlslUniform> {
+    let stripped = strip_comments(src);
+    let code: String = stripped
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with('#') {
+                ""
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let toks = tokens(&code);
+    let mut uniforms = vec![];
+    for stmt in statements(&toks) {
+        let uniform_ix = match stmt.iter().position(|t| t.text == "uniform") {
+            None => continue,
+            Some(ix) => ix,
+        };
+        // Uniform blocks contain a body rather than a type.
+        if stmt.iter().any(|t| t.text == "{") {
+            continue;
+        }
+        let mut rest = stmt[uniform_ix + 1..].iter().map(|t| t.text).peekable();
+        let mut precision = None;
+        let ty = loop {
+            match rest.next() {
+                None => break None,
+                Some(p @ "lowp") | Some(p @ "mediump") | Some(p @ "highp") => {
+                    precision = Some(p.to_string())
+                }
+                Some(ty) if ty.starts_with(is_ident_start) => break Some(ty.to_string()),
+                Some(_) => break None,
+            }
+        };
+        let ty = match ty {
+            None => continue,
+            Some(ty) => ty,
+        };
+        let type_array_len = if rest.peek() == Some(&"[") {
+            array_len(&mut rest)
+        } else {
+            None
+        };
+        // Parse each comma-separated declarator.
+        while let Some(name) = rest.next() {
+            if !name.starts_with(is_ident_start) {
+                break;
+            }
+            let mut array = type_array_len.clone();
+            if rest.peek() == Some(&"[") {
+                array = array_len(&mut rest);
+            }
+            uniforms.push(GlslUniform {
+                name: name.to_string(),
+                ty: ty.clone(),
+                precision: precision.clone(),
+                array_len: array,
+            });
+            // Skip any initializer up to the next declarator.
+            let mut depth = 0;
+            loop {
+                match rest.next() {
+                    None => break,
+                    Some("(") | Some("[") | Some("{") => depth += 1,
+                    Some(")") | Some("]") | Some("}") => depth -= 1,
+                    Some(",") if depth == 0 => break,
+                    Some(_) => (),
+                }
+            }
+        }
+    }
+    uniforms
+}
+
+/// Remove the declaration of the uniform `ident` from the given source.
+///
+/// A statement declaring only `ident` is removed along with its line if nothing else remains on
+/// it, while `ident` alone is removed from a statement declaring several uniforms, e.g. `uniform
+/// float a, b;` becomes `uniform float b;`. Comments and preprocessor directives are ignored.
+pub(crate) fn remove_uniform_declaration(src: &str, ident: &str) -> String {
+    let stripped = strip_comments(src);
+    let code: String = stripped
+        .split_inclusive('\n')
+        .map(|line| {
+            if line.trim_start().starts_with('#') {
+                blank(line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    let toks = tokens(&code);
+    let mut removed = vec![];
+    for stmt in statements(&toks) {
+        let uniform_ix = match stmt.iter().position(|t| t.text == "uniform") {
+            None => continue,
+            Some(ix) => ix,
+        };
+        if stmt.iter().any(|t| t.text == "{") {
+            continue;
+        }
+        // Skip the precision qualifiers, the type and any array specifier on the type.
+        let mut ix = uniform_ix + 1;
+        while matches!(stmt.get(ix).map(|t| t.text), Some("lowp" | "mediump" | "highp")) {
+            ix += 1;
+        }
+        ix += 1;
+        if stmt.get(ix).map(|t| t.text) == Some("[") {
+            while ix < stmt.len() && stmt[ix].text != "]" {
+                ix += 1;
+            }
+            ix += 1;
+        }
+        // Split the remaining tokens into declarators on top-level commas.
+        let mut names = vec![];
+        let mut start = ix;
+        let mut depth = 0;
+        for (ix, tok) in stmt.iter().enumerate().skip(ix) {
+            match tok.text {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                "," if depth == 0 => {
+                    names.push(start..ix);
+                    start = ix + 1;
+                }
+                _ => (),
+            }
+        }
+        if start < stmt.len() {
+            names.push(start..stmt.len());
+        }
+        let is_ident = |d: &Range<usize>| stmt.get(d.start).map(|t| t.text) == Some(ident);
+        let ix = match names.iter().position(is_ident) {
+            None => continue,
+            Some(ix) => ix,
+        };
+        let range = if names.len() == 1 {
+            // Remove the whole statement including its `;`.
+            let end = stmt[stmt.len() - 1].span.end;
+            let semi = end + code[end..].find(';').map_or(0, |i| i + 1);
+            let line_start = code[..stmt[0].span.start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = code[semi..].find('\n').map_or(code.len(), |i| semi + i + 1);
+            let alone = code[line_start..stmt[0].span.start].trim().is_empty()
+                && code[semi..line_end].trim().is_empty();
+            if alone {
+                line_start..line_end
+            } else {
+                stmt[0].span.start..semi
+            }
+        } else if ix + 1 < names.len() {
+            // Remove the declarator along with the following comma.
+            stmt[names[ix].start].span.start..stmt[names[ix + 1].start].span.start
+        } else {
+            // Remove the preceding comma along with the declarator.
+            let prev = &names[ix - 1];
+            stmt[prev.end - 1].span.end..stmt[names[ix].end - 1].span.end
+        };
+        removed.push(range);
+    }
+    let mut out = String::with_capacity(src.len());
+    let mut last = 0;
+    for range in removed {
+        out.push_str(&src[last..range.start]);
+        last = range.end;
+    }
+    out.push_str(&src[last..]);
+    out
+}
+
+/// Split the tokens into `;`-terminated statements, treating a braced block followed by a `;` as
+/// part of the same statement.
+fn statements<'a, 'b>(toks: &'b [Token<'a>]) -> Vec<&'b [Token<'a>]> {
+    let mut stmts = vec![];
+    let mut start = 0;
+    let mut depth = 0i32;
+    for (ix, tok) in toks.iter().enumerate() {
+        match tok.text {
+            "{" => depth += 1,
+            "}" => {
+                depth -= 1;
+                // Function bodies are not followed by a `;`, so end the statement here.
+                if depth == 0 && toks.get(ix + 1).map(|t| t.text) != Some(";") {
+                    start = ix + 1;
+                }
+            }
+            ";" if depth == 0 => {
+                stmts.push(&toks[start..ix]);
+                start = ix + 1;
+            }
+            _ => (),
+        }
+    }
+    stmts
+}
+
+/// Consume the tokens of an array specifier `[...]`, returning the text within the brackets.
+fn array_len<'a, I>(tokens: &mut std::iter::Peekable<I>) -> Option<String>
+where
+    I: Iterator<Item = &'a str>,
+{
+    tokens.next();
+    let mut len = String::new();
+    for tok in tokens.by_ref() {
+        if tok == "]" {
+            break;
+        }
+        len.push_str(tok);
+    }
+    Some(len)
+}
+
+/// Whether the given name may be declared as a GLSL identifier, e.g. as the name of a uniform.
+///
+/// The name must begin with a letter or underscore followed only by letters, digits and
+/// underscores. Names beginning with `gl_` or containing `__` are reserved by GLSL. Note that
+/// GLSL keywords such as `float` are not detected.
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(is_ident_start)
+        && chars.all(is_ident_continue)
+        && !name.starts_with("gl_")
+        && !name.contains("__")
+}
+
+pub(crate) fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+pub(crate) fn is_ident_continue(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Replace every character but newlines with whitespace, preserving the length in bytes.
+fn blank(s: &str) -> String {
+    s.chars()
+        .flat_map(|c| {
+            let b = if c == '\n' { '\n' } else { ' ' };
+            std::iter::repeat_n(b, c.len_utf8())
+        })
+        .collect()
+}
+
+/// The byte range covering the tokens in `start..end`.
+fn span(toks: &[Token], start: usize, end: usize) -> Range<usize> {
+    if start >= end {
+        return 0..0;
+    }
+    toks[start].span.start..toks[end - 1].span.end
+}
+
+/// If the token at `i` begins an `if (PASSINDEX == N)` condition, returns `N` along with the index
+/// of the first token of the guarded statement.
+fn passindex_condition(toks: &[Token], i: usize) -> Option<(usize, usize)> {
+    let text = |ix: usize| toks.get(ix).map(|t| t.text);
+    if text(i)? != "if" || text(i + 1)? != "(" || text(i + 3)? != "==" || text(i + 5)? != ")" {
+        return None;
+    }
+    let (lhs, rhs) = (text(i + 2)?, text(i + 4)?);
+    let literal = match (lhs, rhs) {
+        ("PASSINDEX", lit) | (lit, "PASSINDEX") => lit,
+        _ => return None,
+    };
+    let n = literal.parse().ok()?;
+    Some((n, i + 6))
+}
+
+/// Given the index of the first token of a statement, returns the index one past its last token.
+///
+/// Handles blocks, `if` statements along with their `else` chains and simple statements
+/// terminated by a `;`.
+fn statement_end(toks: &[Token], start: usize) -> usize {
+    match toks.get(start).map(|t| t.text) {
+        None => start,
+        Some("{") => matching_close(toks, start, "{", "}"),
+        Some("if") => {
+            let cond_end = matching_close(toks, start + 1, "(", ")");
+            let end = statement_end(toks, cond_end);
+            match toks.get(end).map(|t| t.text) {
+                Some("else") => statement_end(toks, end + 1),
+                _ => end,
+            }
+        }
+        Some(_) => {
+            let mut depth = 0i32;
+            for (ix, tok) in toks.iter().enumerate().skip(start) {
+                match tok.text {
+                    "(" | "{" | "[" => depth += 1,
+                    ")" | "}" | "]" => depth -= 1,
+                    ";" if depth <= 0 => return ix + 1,
+                    _ => (),
+                }
+            }
+            toks.len()
+        }
+    }
+}
+
+/// Given the index of an opening token, returns the index one past its matching closing token.
+fn matching_close(toks: &[Token], open_ix: usize, open: &str, close: &str) -> usize {
+    let mut depth = 0;
+    for (ix, tok) in toks.iter().enumerate().skip(open_ix) {
+        if tok.text == open {
+            depth += 1;
+        } else if tok.text == close {
+            depth -= 1;
+            if depth == 0 {
+                return ix + 1;
+            }
+        }
+    }
+    toks.len()
+}
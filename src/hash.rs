@@ -0,0 +1,132 @@
+//! Stable hashing of ISF shaders for use as cache keys.
+//!
+//! All hashes are computed with the 64-bit [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/)
+//! algorithm over a canonical byte encoding, so that they are reproducible across platforms,
+//! processes and versions of Rust.
+//!
+//! Each hashed field is prefixed with its length in bytes as a little-endian `u64` so that
+//! adjacent fields cannot be confused. Metadata is encoded as the JSON serialization of the `Isf`
+//! with the keys of every object sorted, excluding `IMPORTED`. Each import is instead encoded as
+//! its name followed by the bytes of its path, as paths need not be valid unicode.
+
+use crate::Isf;
+use std::collections::BTreeMap;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A 64-bit FNV-1a hasher.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(FNV_OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= u64::from(b);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Write the length of the field followed by the field itself.
+    fn write_field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+}
+
+impl Isf {
+    /// A stable hash of the shader's metadata.
+    ///
+    /// Two `Isf`s that serialize to the same JSON (ignoring the order of object keys) produce the
+    /// same hash.
+    pub fn metadata_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+        write_metadata(&mut hasher, self);
+        hasher.0
+    }
+}
+
+/// A stable hash of everything that affects the rendered output of a shader.
+///
+/// The hash covers, in order:
+///
+/// 1. The canonical metadata (see the [module docs](./index.html)).
+/// 2. The GLSL body.
+/// 3. For each entry of `IMPORTED` in order of name: the bytes of the imported image as found
+///    within `import_bytes` under the same name. Imports missing from `import_bytes` hash a single
+///    `0` byte in place of their bytes, distinguishing them from imports whose bytes are empty.
+///
+/// Entries of `import_bytes` that are not imported by the shader are ignored.
+pub fn resolved_shader_hash(
+    isf: &Isf,
+    body: &str,
+    import_bytes: &BTreeMap<String, Vec<u8>>,
+) -> u64 {
+    let mut hasher = Fnv1a::new();
+    write_metadata(&mut hasher, isf);
+    hasher.write_field(body.as_bytes());
+    for name in isf.imported.keys() {
+        match import_bytes.get(name) {
+            Some(bytes) => {
+                hasher.write(&[1]);
+                hasher.write_field(bytes);
+            }
+            None => hasher.write(&[0]),
+        }
+    }
+    hasher.0
+}
+
+/// Write the canonical metadata of the `Isf`, as described within the [module docs](./index.html).
+fn write_metadata(hasher: &mut Fnv1a, isf: &Isf) {
+    hasher.write_field(canonical_json(isf).as_bytes());
+    for (name, import) in &isf.imported {
+        hasher.write_field(name.as_bytes());
+        hasher.write_field(import.path.as_os_str().as_encoded_bytes());
+    }
+}
+
+/// Serialize the `Isf` to JSON with the keys of all objects sorted, excluding `IMPORTED`.
+fn canonical_json(isf: &Isf) -> String {
+    let isf = Isf {
+        imported: BTreeMap::new(),
+        ..isf.clone()
+    };
+    // Import paths are the only values that may fail to serialize.
+    let value = serde_json::to_value(&isf).expect("failed to serialize `Isf` to JSON");
+    let mut json = String::new();
+    write_canonical(&value, &mut json);
+    json
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Array(arr) => {
+            out.push('[');
+            for (ix, v) in arr.iter().enumerate() {
+                if ix > 0 {
+                    out.push(',');
+                }
+                write_canonical(v, out);
+            }
+            out.push(']');
+        }
+        serde_json::Value::Object(obj) => {
+            let sorted: BTreeMap<_, _> = obj.iter().collect();
+            out.push('{');
+            for (ix, (k, v)) in sorted.into_iter().enumerate() {
+                if ix > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(k.clone()).to_string());
+                out.push(':');
+                write_canonical(v, out);
+            }
+            out.push('}');
+        }
+        v => out.push_str(&v.to_string()),
+    }
+}
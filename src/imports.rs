@@ -0,0 +1,93 @@
+//! Validation and resolution of the images imported by an ISF shader.
+
+use crate::{ImageImport, Isf};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// An issue with an entry within an ISF shader's `IMPORTED` dict.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportIssue {
+    /// The import's path has no extension, so its format cannot be determined.
+    MissingExtension { name: String, path: PathBuf },
+    /// The import's path has an extension that is not within the allowed set.
+    UnsupportedExtension {
+        name: String,
+        path: PathBuf,
+        ext: String,
+    },
+}
+
+impl fmt::Display for ImportIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ImportIssue::MissingExtension { ref name, ref path } => write!(
+                f,
+                "the path `{}` of import `{}` has no extension",
+                path.display(),
+                name
+            ),
+            ImportIssue::UnsupportedExtension {
+                ref name,
+                ref path,
+                ref ext,
+            } => write!(
+                f,
+                "the path `{}` of import `{}` has unsupported extension `{}`",
+                path.display(),
+                name,
+                ext
+            ),
+        }
+    }
+}
+
+impl ImageImport {
+    /// The path of the image resolved against the directory containing the shader.
+    ///
+    /// Relative paths are joined onto `base_dir` while absolute paths are returned unchanged.
+    pub fn resolved_path(&self, base_dir: &Path) -> PathBuf {
+        if self.path.is_absolute() {
+            self.path.clone()
+        } else {
+            base_dir.join(&self.path)
+        }
+    }
+}
+
+impl Isf {
+    /// Resolve the path of every `IMPORTED` image against the directory containing the shader.
+    ///
+    /// Import paths are usually relative to the shader file. See
+    /// [**ImageImport::resolved_path**](./struct.ImageImport.html#method.resolved_path).
+    pub fn resolve_imports(&mut self, base_dir: &Path) {
+        for import in self.imported.values_mut() {
+            import.path = import.resolved_path(base_dir);
+        }
+    }
+
+    /// Check that the path of every `IMPORTED` image has one of the given extensions.
+    ///
+    /// Extensions are compared case-insensitively and may be given with or without a leading
+    /// `.`, e.g. `&["png", ".jpg"]`. Issues are returned in the order of the import names.
+    pub fn validate_imports(&self, allowed_exts: &[&str]) -> Vec<ImportIssue> {
+        let allowed: Vec<String> = allowed_exts
+            .iter()
+            .map(|ext| ext.trim_start_matches('.').to_lowercase())
+            .collect();
+        self.imported
+            .iter()
+            .filter_map(|(name, import)| {
+                let name = name.clone();
+                let path = import.path.clone();
+                let ext = match import.path.extension() {
+                    None => return Some(ImportIssue::MissingExtension { name, path }),
+                    Some(ext) => ext.to_string_lossy().into_owned(),
+                };
+                if allowed.contains(&ext.to_lowercase()) {
+                    return None;
+                }
+                Some(ImportIssue::UnsupportedExtension { name, path, ext })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,46 @@
+//! Lazy parsing of the passes of an ISF shader.
+
+use crate::{top_comment_contents, Isf, ParseError, Pass};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+/// The top-level dict with all fields but the `PASSES` skipped.
+#[derive(Deserialize)]
+struct RawPasses<'a> {
+    #[serde(borrow, default, rename = "PASSES")]
+    passes: Vec<&'a RawValue>,
+}
+
+/// Parse only the `PASSES` of the ISF blob within a GLSL source string, deserializing each pass
+/// lazily as the iterator is advanced.
+///
+/// All other fields of the top-level dict are skipped without being deserialized. The JSON of
+/// each pass is borrowed from `glsl_src` until the pass is yielded, so passes that are not reached
+/// are never deserialized. This is useful for scanning the passes of procedurally generated
+/// shaders with many passes where the inputs are irrelevant.
+///
+/// If the top comment is missing or the JSON is malformed, the iterator yields a single error.
+pub fn parse_passes_only(glsl_src: &str) -> impl Iterator<Item = Result<Pass, ParseError>> + '_ {
+    let (passes, err) = match raw_passes(glsl_src) {
+        Ok(passes) => (passes, None),
+        Err(err) => (vec![], Some(err)),
+    };
+    let passes = passes.into_iter().map(move |raw| {
+        serde_json::from_str(raw.get()).map_err(|err| ParseError::json(err, glsl_src, raw.get()))
+    });
+    err.map(Err).into_iter().chain(passes)
+}
+
+fn raw_passes(glsl_src: &str) -> Result<Vec<&RawValue>, ParseError> {
+    let comment_contents = top_comment_contents(glsl_src).ok_or(ParseError::MissingTopComment)?;
+    let raw: RawPasses = serde_json::from_str(comment_contents)
+        .map_err(|err| ParseError::json(err, glsl_src, comment_contents))?;
+    Ok(raw.passes)
+}
+
+impl Isf {
+    /// An iterator yielding a reference to each of the shader's passes in order.
+    pub fn iter_passes(&self) -> std::slice::Iter<'_, Pass> {
+        self.passes.iter()
+    }
+}
@@ -0,0 +1,88 @@
+//! Lenient parsing of ISF blobs produced by non-conforming tools.
+
+use crate::{top_comment_contents, Isf, ParseError};
+
+/// Attempt to parse an ISF blob from a GLSL source string, repairing common mistakes made by
+/// non-conforming exporters before parsing the JSON.
+///
+/// Currently the following repairs are made:
+///
+/// - Numbers written with a locale decimal comma in an object value position (e.g.
+///   `"DEFAULT": 0,5`) are rewritten with a decimal point. As commas are structural within JSON,
+///   only a comma that directly follows the integer part of a number and is directly followed by
+///   a digit is considered. Numbers within arrays (e.g. `[0,5]`) are never modified, as these are
+///   already valid JSON.
+///
+/// Prefer [**parse**](./fn.parse.html) where possible.
+pub fn parse_lenient(glsl_src: &str) -> Result<Isf, ParseError> {
+    let comment_contents = top_comment_contents(glsl_src).ok_or(ParseError::MissingTopComment)?;
+    let json = repair_decimal_commas(comment_contents);
+    // Repairs replace single characters, so positions within the JSON are unchanged.
+    serde_json::from_str(&json).map_err(|err| ParseError::json(err, glsl_src, comment_contents))
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Container {
+    Object,
+    Array,
+}
+
+/// Replace decimal commas within numbers in object value positions with decimal points.
+fn repair_decimal_commas(json: &str) -> String {
+    let bytes = json.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut stack = vec![];
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => (),
+            }
+            out.push(b);
+            i += 1;
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' => stack.push(Container::Object),
+            b'[' => stack.push(Container::Array),
+            b'}' | b']' => {
+                stack.pop();
+            }
+            b'-' | b'0'..=b'9' => {
+                let len = bytes[i..]
+                    .iter()
+                    .take_while(|b| matches!(b, b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-'))
+                    .count();
+                let number = &bytes[i..i + len];
+                out.extend_from_slice(number);
+                i += len;
+                let is_integer = !number.iter().any(|b| matches!(b, b'.' | b'e' | b'E'));
+                let in_object = stack.last() == Some(&Container::Object);
+                let decimal_comma =
+                    bytes.get(i) == Some(&b',') && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+                if in_object && is_integer && decimal_comma {
+                    let frac_len = bytes[i + 1..]
+                        .iter()
+                        .take_while(|b| b.is_ascii_digit())
+                        .count();
+                    out.push(b'.');
+                    out.extend_from_slice(&bytes[i + 1..i + 1 + frac_len]);
+                    i += 1 + frac_len;
+                }
+                continue;
+            }
+            _ => (),
+        }
+        out.push(b);
+        i += 1;
+    }
+    // Only ASCII commas were replaced with ASCII periods, so the result remains valid UTF-8.
+    String::from_utf8(out).expect("repairing decimal commas produced invalid UTF-8")
+}
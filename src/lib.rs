@@ -7,33 +7,172 @@
 //!
 //! The [**parse**](./fn.parse.html) function can parse a given GLSL string to produce an
 //! [**Isf**](./struct.Isf.html) instance. The **Isf** type represents a fully structured
-//! representation of the format, including typed [**Input**](./struct.Input.html)s.
+//! representation of the format, including typed [**Input**](./struct.Input.html)s. The
+//! [**parse_file**](./fn.parse_file.html) function reads and parses a file in one step, reporting
+//! IO errors via the same [**ParseError**](./enum.ParseError.html) type.
+//!
+//! Parsing and (de)serialization require the default `serde` feature. With it disabled, the types
+//! describing a shader remain available along with their inherent methods, e.g. for constructing,
+//! validating or generating code for shaders at runtime.
 
+#[cfg(feature = "serde")]
+use color::ColorComponents;
+#[cfg(feature = "serde")]
+use serde::de::{value::MapAccessDeserializer, MapAccess, SeqAccess, Visitor};
+#[cfg(feature = "serde")]
+use serde::ser::SerializeMap;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::BTreeMap;
-use std::ops::Deref;
+use std::ops::{Deref, Range};
+#[cfg(feature = "serde")]
+use std::path::Path;
 use std::path::PathBuf;
 use thiserror::Error;
 
+pub use builder::{InputBuilder, IsfBuilder, PassBuilder};
+pub use color::{color_to_rgba, Color, ColorError, HexColorError};
+#[cfg(feature = "serde")]
+pub use comments::{
+    parse_with_comments, to_string_pretty_with_comments, CommentAnchor, JsonComments,
+};
+pub use deps::CycleError;
+pub use diff::{Carryover, InterfaceDiff};
+#[cfg(feature = "base64")]
+pub use encoded::{parse_encoded, to_encoded_comment, DATA_URI_PREFIX};
+pub use expr::{BinOp, DimContext, DimExpr, EvalError, ExprError, Func, PassSizeError};
+#[cfg(feature = "serde")]
+pub use extract::{parse_html_embedded, parse_with_options, ExtractOptions};
+#[cfg(feature = "toml")]
+pub use format::{from_toml, to_toml};
+#[cfg(feature = "yaml")]
+pub use format::{from_yaml, to_yaml};
+#[cfg(feature = "serde")]
+pub use hash::resolved_shader_hash;
+pub use imports::ImportIssue;
+#[cfg(feature = "serde")]
+pub use lazy::parse_passes_only;
+#[cfg(feature = "serde")]
+pub use lenient::parse_lenient;
+#[cfg(feature = "serde")]
+pub use lint::parse_verbose;
+pub use lint::{LintOptions, LintReport, Warning};
+pub use point::Point2d;
+pub use preamble::{standard_preamble, GlslVersion};
+pub use reserved::{is_reserved_name, RESERVED_NAMES};
+#[cfg(feature = "serde")]
+pub use roundtrip::{roundtrip_check, RoundtripMismatch};
+#[cfg(feature = "schema")]
+pub use schema::json_schema;
+#[cfg(feature = "serde")]
+pub use ser::{to_string_with_options, SerializeOptions};
+#[cfg(feature = "serde")]
+use ser::{Dict, SerializeDict};
+pub use texture::{TextureKind, TextureRequirement};
+pub use uniform::{Uniform, UniformLayout, UniformOutOfBounds, UniformType};
+pub use validate::ValidationError;
+pub use value::{InputValue, TypeMismatch};
+pub use version::{IsfVersion, UpgradeReport};
+#[cfg(feature = "serde")]
+pub use write::write_comment;
+
+mod bake;
+mod builder;
+mod color;
+#[cfg(feature = "serde")]
+mod comments;
+mod deps;
+mod diff;
+#[cfg(feature = "base64")]
+mod encoded;
+mod expr;
+#[cfg(feature = "serde")]
+mod extract;
+mod format;
+pub mod glsl;
+#[cfg(feature = "serde")]
+mod hash;
+mod imports;
+#[cfg(feature = "serde")]
+mod lazy;
+#[cfg(feature = "serde")]
+mod lenient;
+mod lint;
+mod markdown;
+mod point;
+mod preamble;
+mod reserved;
+#[cfg(feature = "serde")]
+mod roundtrip;
+#[cfg(feature = "schema")]
+mod schema;
+#[cfg(feature = "serde")]
+mod ser;
+mod texture;
+mod uniform;
+mod validate;
+mod value;
+mod version;
+#[cfg(feature = "serde")]
+mod write;
+
 /// Representation of the JSON structure parsed from the top-level GLSL comment.
 ///
 /// This is referred to as the "top-level dict" in the spec.
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+///
+/// The `Default` instance is a blank shader without any metadata, inputs or passes.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
 pub struct Isf {
-    #[serde(default, rename = "ISFVSN")]
+    #[cfg_attr(feature = "serde", serde(default, rename = "ISFVSN"))]
     pub isfvsn: Option<String>,
-    #[serde(default, rename = "VSN")]
+    #[cfg_attr(feature = "serde", serde(default, rename = "VSN"))]
     pub vsn: Option<String>,
-    #[serde(default, rename = "DESCRIPTION")]
+    #[cfg_attr(feature = "serde", serde(default, rename = "DESCRIPTION"))]
     pub description: Option<String>,
-    #[serde(default, rename = "CATEGORIES")]
+    /// The shader's display name, as used by some ISF hosts.
+    #[cfg_attr(feature = "serde", serde(default, rename = "LABEL"))]
+    pub label: Option<String>,
+    /// Credit for the author(s) of the shader.
+    #[cfg_attr(feature = "serde", serde(default, rename = "CREDIT"))]
+    pub credit: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default, rename = "CATEGORIES"))]
     pub categories: Vec<String>,
-    #[serde(default, rename = "INPUTS")]
+    #[cfg_attr(feature = "serde", serde(default, rename = "INPUTS"))]
     pub inputs: Vec<Input>,
-    #[serde(default, rename = "PASSES")]
+    #[cfg_attr(feature = "serde", serde(default, rename = "PASSES"))]
     pub passes: Vec<Pass>,
-    #[serde(default, rename = "IMPORTED")]
+    /// Images imported by the shader, keyed by name.
+    ///
+    /// Both the dict form and the array form, in which each import carries its own `NAME`, are
+    /// accepted. The dict form is always emitted.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "IMPORTED",
+            deserialize_with = "deserialize_imported"
+        )
+    )]
     pub imported: BTreeMap<String, ImageImport>,
+    /// Persistent buffers as declared by ISF v1, keyed by name.
+    ///
+    /// ISF v2 replaces these with the `PERSISTENT` flag on each pass. See
+    /// [**Isf::upgrade_to_v2**](./struct.Isf.html#method.upgrade_to_v2).
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "PERSISTENT_BUFFERS",
+            deserialize_with = "deserialize_persistent_buffers"
+        )
+    )]
+    pub persistent_buffers: BTreeMap<String, PersistentBuffer>,
+    /// Top-level keys that are not described by the spec, e.g. extensions specific to a host.
+    ///
+    /// These are preserved as-is so that they are emitted again upon serialization.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub extra: BTreeMap<String, serde_json::Value>,
 }
 
 /// Describes an input to the ISF shader.
@@ -41,7 +180,12 @@ pub struct Isf {
 pub struct Input {
     pub name: String,
     pub label: Option<String>,
+    /// Longer help text describing the input (e.g. for a tooltip), stored as `HELP`.
+    pub help: Option<String>,
     pub ty: InputType,
+    /// Keys of the input's dict that are not described by the spec, e.g. those specific to a
+    /// particular editor. These are emitted again upon serialization.
+    pub extra: BTreeMap<String, serde_json::Value>,
 }
 
 /// Input types supported by ISF.
@@ -58,16 +202,31 @@ pub enum InputType {
     AudioFft(InputAudioFft),
 }
 
+/// The kind of an `InputType` without any of its values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InputTypeKind {
+    Event,
+    Bool,
+    Long,
+    Float,
+    Point2d,
+    Color,
+    Image,
+    Audio,
+    AudioFft,
+}
+
 /// Possible values stored for the type.
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct InputValues<T> {
-    #[serde(rename = "DEFAULT")]
+    #[cfg_attr(feature = "serde", serde(rename = "DEFAULT"))]
     pub default: Option<T>,
-    #[serde(rename = "MIN")]
+    #[cfg_attr(feature = "serde", serde(rename = "MIN"))]
     pub min: Option<T>,
-    #[serde(rename = "MAX")]
+    #[cfg_attr(feature = "serde", serde(rename = "MAX"))]
     pub max: Option<T>,
-    #[serde(rename = "IDENTITY")]
+    #[cfg_attr(feature = "serde", serde(rename = "IDENTITY"))]
     pub identity: Option<T>,
 }
 
@@ -85,27 +244,61 @@ pub struct InputLong {
 
 pub type InputFloat = InputValues<f32>;
 
-pub type InputPoint2d = InputValues<[f32; 2]>;
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputPoint2d {
+    pub input_values: InputValues<Point2d>,
+    /// The space in which the point's values are specified, stored as `COORDINATE_SPACE`.
+    pub coordinate_space: CoordinateSpace,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputColor {
+    pub input_values: InputValues<Color>,
+    /// Whether the color is RGB rather than RGBA, i.e. its values were written with three
+    /// components rather than four.
+    ///
+    /// RGB colors are represented by a `vec3` uniform and their values are written with three
+    /// components again upon serialization, besides any value with an alpha other than `1.0`.
+    pub rgb: bool,
+}
 
-pub type InputColor = InputValues<Vec<f32>>;
+/// The coordinate space in which the values of a `point2D` input are specified.
+///
+/// The ISF spec does not state a space for `point2D` values, so this is an extension to the format
+/// stored under the `COORDINATE_SPACE` key. Inputs without the key are assumed to be normalized.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum CoordinateSpace {
+    /// Coordinates are relative to the render size, where `[1.0, 1.0]` is the far corner.
+    #[default]
+    Normalized,
+    /// Coordinates are in pixels.
+    Pixel,
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct InputAudio {
+    /// The number of samples the host should provide, stored as `MAX` as per the spec.
     pub num_samples: Option<u32>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct InputAudioFft {
+    /// The number of FFT columns the host should provide, stored as `MAX` as per the spec.
     pub num_columns: Option<u32>,
 }
 
-/// A helper type to simplify implementation of serialize/deserialize for `Input`.
-#[derive(Debug, Deserialize, Serialize)]
+/// A helper type to simplify implementation of deserialize for `Input`.
+#[cfg(feature = "serde")]
+#[derive(Debug, Deserialize)]
 struct InputDict {
     #[serde(rename = "NAME")]
     pub name: String,
     #[serde(rename = "LABEL")]
     pub label: Option<String>,
+    #[serde(default, rename = "HELP")]
+    pub help: Option<String>,
     #[serde(rename = "TYPE")]
     pub ty: String,
     #[serde(default, rename = "DEFAULT")]
@@ -120,43 +313,305 @@ struct InputDict {
     pub values: Vec<i32>,
     #[serde(default, rename = "LABELS")]
     pub labels: Vec<String>,
+    #[serde(default, rename = "COORDINATE_SPACE")]
+    pub coordinate_space: Option<CoordinateSpace>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
 }
 
 /// Describes a pass of an ISF shader.
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Pass {
+    pub target: Option<String>,
+    pub persistent: bool,
+    pub float: bool,
+    pub width: Option<String>,
+    pub height: Option<String>,
+    /// Whether the `WIDTH` is a JSON number rather than an expression string, e.g.
+    /// `"WIDTH": 512`. Such a `WIDTH` is written as a number upon serialization.
+    pub width_is_number: bool,
+    /// Whether the `HEIGHT` is a JSON number, as per
+    /// [**width_is_number**](#structfield.width_is_number).
+    pub height_is_number: bool,
+}
+
+/// A helper type to simplify implementation of deserialize for `Pass`.
+#[cfg(feature = "serde")]
+#[derive(Debug, Deserialize)]
+struct PassDict {
     #[serde(default, rename = "TARGET")]
     pub target: Option<String>,
     #[serde(default, deserialize_with = "deserialize_bool", rename = "PERSISTENT")]
     pub persistent: bool,
     #[serde(default, deserialize_with = "deserialize_bool", rename = "FLOAT")]
     pub float: bool,
-    #[serde(default, deserialize_with = "deserialize_opt_string", rename = "WIDTH")]
+    #[serde(default, rename = "WIDTH")]
+    pub width: Option<serde_json::Value>,
+    #[serde(default, rename = "HEIGHT")]
+    pub height: Option<serde_json::Value>,
+}
+
+/// A persistent buffer as declared within the ISF v1 `PERSISTENT_BUFFERS`.
+///
+/// v1 allows `PERSISTENT_BUFFERS` to be either an array of buffer names or a dict mapping each
+/// name to a description of the buffer. Buffers declared via the array form use the default of
+/// each field.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PersistentBuffer {
+    pub float: bool,
     pub width: Option<String>,
-    #[serde(default, deserialize_with = "deserialize_opt_string", rename = "HEIGHT")]
     pub height: Option<String>,
+    /// Whether the `WIDTH` is a JSON number, as per
+    /// [**Pass::width_is_number**](./struct.Pass.html#structfield.width_is_number).
+    pub width_is_number: bool,
+    /// Whether the `HEIGHT` is a JSON number, as per
+    /// [**Pass::width_is_number**](./struct.Pass.html#structfield.width_is_number).
+    pub height_is_number: bool,
+}
+
+/// A helper type to simplify implementation of deserialize for `PersistentBuffer`.
+#[cfg(feature = "serde")]
+#[derive(Debug, Deserialize)]
+struct PersistentBufferDict {
+    #[serde(default, deserialize_with = "deserialize_bool", rename = "FLOAT")]
+    pub float: bool,
+    #[serde(default, rename = "WIDTH")]
+    pub width: Option<serde_json::Value>,
+    #[serde(default, rename = "HEIGHT")]
+    pub height: Option<serde_json::Value>,
 }
 
 /// A described image import
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct ImageImport {
-    #[serde(rename = "PATH")]
+    #[cfg_attr(feature = "serde", serde(rename = "PATH"))]
     pub path: PathBuf,
 }
 
+/// The GLSL source code that follows the ISF comment.
+///
+/// Line numbers are 1-based. The first line of `src` is line `line` of the original source,
+/// noting that this line begins directly after the closing `*/` of the ISF comment. Line `n` of
+/// `src` is therefore line `line + n - 1` of the original source.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Body<'a> {
+    /// The GLSL source following the ISF comment, exactly as it appears in the original source.
+    pub src: &'a str,
+    /// The byte offset of `src` within the original source.
+    pub offset: usize,
+    /// The line within the original source on which `src` begins.
+    pub line: usize,
+}
+
+/// An input `TYPE` that is not one of the types supported by ISF.
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error(
+    "unknown ISF input type \"{ty}\", expected one of: {}",
+    InputType::ALL_TYPE_NAMES.join(", ")
+)]
+pub struct UnknownInputType {
+    /// The unrecognised `TYPE` string.
+    pub ty: String,
+}
+
 /// Errors that might occur while parsing a GLSL string for an ISF blob.
 #[derive(Debug, Error)]
 pub enum ParseError {
+    #[error("failed to read ISF file {}: {err}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        err: std::io::Error,
+    },
     #[error("failed to find the top comment containing the JSON blob")]
     MissingTopComment,
-    #[error("failed to parse JSON from the top comment: {err}")]
+    #[cfg(feature = "base64")]
+    #[error("failed to decode the base64 ISF blob: {err}")]
+    Base64 {
+        #[source]
+        err: base64::DecodeError,
+    },
+    #[error("failed to serialize the ISF blob: {err}")]
+    Serialize {
+        #[source]
+        err: serde_json::Error,
+    },
+    #[error("failed to parse JSON at line {line}, column {column} of source: {err}")]
     Json {
-        #[from]
+        #[source]
         err: serde_json::Error,
+        /// The 1-based line within the GLSL source at which the error occurred.
+        line: usize,
+        /// The 1-based column (in bytes) within the line at which the error occurred.
+        column: usize,
     },
 }
 
+impl ParseError {
+    /// Produce a `Json` error, translating the position of the error within `json` to a position
+    /// within `src`.
+    ///
+    /// `json` must be a slice of `src`. The JSON that was actually parsed may differ from `json`
+    /// (e.g. after repairs) so long as the positions of its lines and columns are unchanged.
+    #[cfg(feature = "serde")]
+    pub(crate) fn json(err: serde_json::Error, src: &str, json: &str) -> Self {
+        let (first_line, first_column) = source_position(src, json);
+        let (line, column) = match (err.line(), err.column()) {
+            // Errors without a position (e.g. from invalid data) are reported at the JSON start.
+            (0, _) => (first_line, first_column),
+            (1, column) => (first_line, first_column + column - 1),
+            (line, column) => (first_line + line - 1, column),
+        };
+        ParseError::Json { err, line, column }
+    }
+
+    /// Produce a `Json` error reported at the start of `blob`, a slice of `src`, e.g. for JSON
+    /// that was decoded from the blob and so has no position within `src`.
+    #[cfg(feature = "base64")]
+    pub(crate) fn json_at_start(err: serde_json::Error, src: &str, blob: &str) -> Self {
+        let (line, column) = source_position(src, blob);
+        ParseError::Json { err, line, column }
+    }
+}
+
+impl Isf {
+    /// The shader's display name as specified by the top-level `LABEL`, if any.
+    pub fn title(&self) -> Option<&str> {
+        self.label.as_ref().map(|s| &s[..])
+    }
+
+    /// The input with the given `NAME`.
+    ///
+    /// ISF does not forbid multiple inputs with the same name, in which case the first is
+    /// returned. [**validate**](#method.validate) reports such duplicates.
+    pub fn input(&self, name: &str) -> Option<&Input> {
+        self.inputs.iter().find(|i| i.name == name)
+    }
+
+    /// Mutable access to the input with the given `NAME`.
+    ///
+    /// As with [**input**](#method.input), the first match is returned.
+    pub fn input_mut(&mut self, name: &str) -> Option<&mut Input> {
+        self.inputs.iter_mut().find(|i| i.name == name)
+    }
+
+    /// The `DESCRIPTION` if present and not blank, otherwise `fallback`.
+    pub fn description_or<'a>(&'a self, fallback: &'a str) -> &'a str {
+        match self.description {
+            Some(ref d) if !d.trim().is_empty() => d,
+            _ => fallback,
+        }
+    }
+
+    /// Whether the shader belongs to any of the given categories.
+    ///
+    /// Categories are compared case-insensitively with surrounding whitespace trimmed.
+    pub fn matches_any_category(&self, cats: &[&str]) -> bool {
+        cats.iter().any(|cat| self.has_category(cat))
+    }
+
+    /// Whether the shader belongs to all of the given categories.
+    ///
+    /// Categories are compared case-insensitively with surrounding whitespace trimmed.
+    pub fn matches_all_category(&self, cats: &[&str]) -> bool {
+        cats.iter().all(|cat| self.has_category(cat))
+    }
+
+    /// The value-bearing inputs (`bool`, `long`, `float`, `point2D` and `color`) that do not
+    /// specify a `DEFAULT`.
+    ///
+    /// `event`, `image`, `audio` and `audioFFT` inputs have no notion of a default and are never
+    /// included.
+    pub fn inputs_without_default(&self) -> Vec<&Input> {
+        self.inputs
+            .iter()
+            .filter(|input| match input.ty {
+                InputType::Bool(ref b) => b.default.is_none(),
+                InputType::Long(ref l) => l.default.is_none(),
+                InputType::Float(ref f) => f.default.is_none(),
+                InputType::Point2d(ref p) => p.default.is_none(),
+                InputType::Color(ref c) => c.default.is_none(),
+                InputType::Event
+                | InputType::Image
+                | InputType::Audio(_)
+                | InputType::AudioFft(_) => false,
+            })
+            .collect()
+    }
+
+    fn has_category(&self, cat: &str) -> bool {
+        let cat = normalize_category(cat);
+        self.categories.iter().any(|c| normalize_category(c) == cat)
+    }
+}
+
+/// Deserialize the `Isf` from a JSON value holding the top-level dict, e.g. one nested within a
+/// larger document.
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<serde_json::Value> for Isf {
+    type Error = serde_json::Error;
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        serde_json::from_value(value)
+    }
+}
+
+/// Serialize the `Isf` to a JSON value holding the top-level dict.
+///
+/// Fails if an import `PATH` is not valid unicode.
+#[cfg(feature = "serde")]
+impl<'a> std::convert::TryFrom<&'a Isf> for serde_json::Value {
+    type Error = serde_json::Error;
+    fn try_from(isf: &'a Isf) -> Result<Self, Self::Error> {
+        serde_json::to_value(isf)
+    }
+}
+
+impl<'a> Body<'a> {
+    /// Map a 1-based line number within the body to the line number within the original source.
+    pub fn source_line(&self, body_line: usize) -> usize {
+        self.line + body_line.saturating_sub(1)
+    }
+}
+
 impl<T> InputValues<T> {
+    /// Replace the `DEFAULT`.
+    pub fn with_default(mut self, default: T) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Replace the `MIN` and `MAX`.
+    pub fn with_range(mut self, min: T, max: T) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    /// Replace the `IDENTITY`.
+    pub fn with_identity(mut self, identity: T) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// The `DEFAULT`, or `fallback` if there is none.
+    pub fn default_or(&self, fallback: T) -> T
+    where
+        T: Clone,
+    {
+        self.default.clone().unwrap_or(fallback)
+    }
+
+    /// The `IDENTITY`, i.e. the value at which the input has no effect, or `fallback` if there
+    /// is none.
+    pub fn identity_or(&self, fallback: T) -> T
+    where
+        T: Clone,
+    {
+        self.identity.clone().unwrap_or(fallback)
+    }
+
+    #[cfg(feature = "serde")]
     fn from_opts(
         default: Option<serde_json::Value>,
         min: Option<serde_json::Value>,
@@ -166,38 +621,274 @@ impl<T> InputValues<T> {
     where
         T: for<'de> Deserialize<'de>,
     {
-        let default = match default {
-            Some(t) => Some(serde_json::from_value(t)?),
-            None => None,
-        };
-        let min = match min {
-            Some(t) => Some(serde_json::from_value(t)?),
-            None => None,
-        };
-        let max = match max {
-            Some(t) => Some(serde_json::from_value(t)?),
-            None => None,
-        };
-        let identity = match identity {
-            Some(t) => Some(serde_json::from_value(t)?),
-            None => None,
-        };
         Ok(InputValues {
-            default,
-            min,
-            max,
-            identity,
+            default: from_opt_value(default)?,
+            min: from_opt_value(min)?,
+            max: from_opt_value(max)?,
+            identity: from_opt_value(identity)?,
         })
     }
+}
 
-    fn write_to_dict(&self, dict: &mut InputDict)
-    where
-        T: Clone + Into<serde_json::Value>,
-    {
-        dict.default = self.default.as_ref().map(|t| t.clone().into());
-        dict.min = self.min.as_ref().map(|t| t.clone().into());
-        dict.max = self.max.as_ref().map(|t| t.clone().into());
-        dict.identity = self.identity.as_ref().map(|t| t.clone().into());
+impl InputType {
+    /// The `TYPE` string of every input type supported by ISF, as they appear in the JSON.
+    pub const ALL_TYPE_NAMES: &'static [&'static str] = &[
+        "event", "bool", "long", "float", "point2D", "color", "image", "audio", "audioFFT",
+    ];
+
+    /// The `TYPE` string matching `ty` case-insensitively, in the casing of the spec.
+    ///
+    /// Editors disagree on the casing of input types, e.g. `"POINT2D"` or `"audiofft"`, so these
+    /// are canonicalized to `"point2D"` and `"audioFFT"` upon parsing. Returns `None` for unknown
+    /// types.
+    pub fn canonical_type_str(ty: &str) -> Option<&'static str> {
+        Self::ALL_TYPE_NAMES
+            .iter()
+            .copied()
+            .find(|name| name.eq_ignore_ascii_case(ty))
+    }
+
+    /// The `TYPE` string of the input type as it appears in the JSON, e.g. `"point2D"`.
+    pub fn type_str(&self) -> &'static str {
+        match *self {
+            InputType::Event => "event",
+            InputType::Bool(_) => "bool",
+            InputType::Long(_) => "long",
+            InputType::Float(_) => "float",
+            InputType::Point2d(_) => "point2D",
+            InputType::Color(_) => "color",
+            InputType::Image => "image",
+            InputType::Audio(_) => "audio",
+            InputType::AudioFft(_) => "audioFFT",
+        }
+    }
+
+    /// The kind of the input type.
+    pub fn kind(&self) -> InputTypeKind {
+        match *self {
+            InputType::Event => InputTypeKind::Event,
+            InputType::Bool(_) => InputTypeKind::Bool,
+            InputType::Long(_) => InputTypeKind::Long,
+            InputType::Float(_) => InputTypeKind::Float,
+            InputType::Point2d(_) => InputTypeKind::Point2d,
+            InputType::Color(_) => InputTypeKind::Color,
+            InputType::Image => InputTypeKind::Image,
+            InputType::Audio(_) => InputTypeKind::Audio,
+            InputType::AudioFft(_) => InputTypeKind::AudioFft,
+        }
+    }
+}
+
+impl InputTypeKind {
+    /// Produce an input type of this kind with no values specified.
+    pub fn default_type(&self) -> InputType {
+        match *self {
+            InputTypeKind::Event => InputType::Event,
+            InputTypeKind::Bool => InputType::Bool(InputBool { default: None }),
+            InputTypeKind::Long => InputType::Long(InputLong {
+                input_values: Default::default(),
+                values: vec![],
+                labels: vec![],
+            }),
+            InputTypeKind::Float => InputType::Float(Default::default()),
+            InputTypeKind::Point2d => InputType::Point2d(InputPoint2d {
+                input_values: Default::default(),
+                coordinate_space: Default::default(),
+            }),
+            InputTypeKind::Color => InputType::Color(Default::default()),
+            InputTypeKind::Image => InputType::Image,
+            InputTypeKind::Audio => InputType::Audio(InputAudio { num_samples: None }),
+            InputTypeKind::AudioFft => InputType::AudioFft(InputAudioFft { num_columns: None }),
+        }
+    }
+}
+
+impl std::str::FromStr for InputTypeKind {
+    type Err = UnknownInputType;
+
+    /// Parse a `TYPE` string as it appears in the JSON, e.g. `"point2D"`. The comparison is
+    /// case-insensitive, as it is when deserializing an input.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let kind = match InputType::canonical_type_str(s) {
+            Some("event") => InputTypeKind::Event,
+            Some("bool") => InputTypeKind::Bool,
+            Some("long") => InputTypeKind::Long,
+            Some("float") => InputTypeKind::Float,
+            Some("point2D") => InputTypeKind::Point2d,
+            Some("color") => InputTypeKind::Color,
+            Some("image") => InputTypeKind::Image,
+            Some("audio") => InputTypeKind::Audio,
+            Some("audioFFT") => InputTypeKind::AudioFft,
+            _ => return Err(UnknownInputType { ty: s.to_string() }),
+        };
+        Ok(kind)
+    }
+}
+
+impl<T> Default for InputValues<T> {
+    fn default() -> Self {
+        InputValues {
+            default: None,
+            min: None,
+            max: None,
+            identity: None,
+        }
+    }
+}
+
+impl From<InputBool> for InputType {
+    fn from(t: InputBool) -> Self {
+        InputType::Bool(t)
+    }
+}
+
+impl From<InputLong> for InputType {
+    fn from(t: InputLong) -> Self {
+        InputType::Long(t)
+    }
+}
+
+impl From<InputFloat> for InputType {
+    fn from(t: InputFloat) -> Self {
+        InputType::Float(t)
+    }
+}
+
+impl From<InputPoint2d> for InputType {
+    fn from(t: InputPoint2d) -> Self {
+        InputType::Point2d(t)
+    }
+}
+
+impl From<InputColor> for InputType {
+    fn from(t: InputColor) -> Self {
+        InputType::Color(t)
+    }
+}
+
+impl From<InputAudio> for InputType {
+    fn from(t: InputAudio) -> Self {
+        InputType::Audio(t)
+    }
+}
+
+impl From<InputAudioFft> for InputType {
+    fn from(t: InputAudioFft) -> Self {
+        InputType::AudioFft(t)
+    }
+}
+
+impl Input {
+    /// Replace the name of the input.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Replace the label of the input.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// The `HELP` text describing the input, if any.
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+
+    /// Whether or not the input's type is of a different kind to `kind`.
+    pub fn type_changed(&self, kind: InputTypeKind) -> bool {
+        self.ty.kind() != kind
+    }
+
+    /// Change the input's type to the given kind, e.g. when selected from a shader editor's type
+    /// dropdown.
+    ///
+    /// The `name` and `label` are preserved. If the kind differs from the current type, the type
+    /// is replaced with one of the new kind with no values specified (see
+    /// [**InputTypeKind::default_type**](./enum.InputTypeKind.html#method.default_type)) and all
+    /// existing values (e.g. `DEFAULT`, `MIN`, `MAX` and `VALUES`) are discarded. Retyping to the
+    /// current kind leaves the input unchanged.
+    pub fn retype(&mut self, kind: InputTypeKind) {
+        if self.type_changed(kind) {
+            self.ty = kind.default_type();
+        }
+    }
+
+    /// The `DEFAULT` of a scalar numeric input (`float` or `long`) as an `f64`.
+    ///
+    /// Returns `None` for all other input types or if no default is specified.
+    pub fn numeric_default(&self) -> Option<f64> {
+        match self.ty {
+            InputType::Float(ref f) => f.default.map(Into::into),
+            InputType::Long(ref l) => l.default.map(Into::into),
+            _ => None,
+        }
+    }
+
+    /// The `MIN` and `MAX` of a scalar numeric input (`float` or `long`) as `f64`s.
+    ///
+    /// Returns `None` for all other input types or if either bound is missing.
+    pub fn numeric_bounds(&self) -> Option<(f64, f64)> {
+        match self.ty {
+            InputType::Float(ref f) => Some((f.min?.into(), f.max?.into())),
+            InputType::Long(ref l) => Some((l.min?.into(), l.max?.into())),
+            _ => None,
+        }
+    }
+
+    /// Explode a `point2D` or `color` input into a synthetic `float` input per component.
+    ///
+    /// A `point2D` input named `pos` produces `pos.x` and `pos.y`. A `color` input named `tint`
+    /// produces `tint.r`, `tint.g`, `tint.b` and `tint.a` (or only the first three if the input's
+    /// values all have three components). Each component input takes the respective component of
+    /// the original `DEFAULT`, `MIN`, `MAX` and `IDENTITY`. Color components without a `MIN` or
+    /// `MAX` are given the normalised range `0.0` to `1.0`.
+    ///
+    /// The original value may be recombined by collecting the component values in the order in
+    /// which they are returned. All other input types are returned as a single clone of `self`.
+    pub fn explode_scalars(&self) -> Vec<Input> {
+        let component = |suffix: &str, values: InputFloat| Input {
+            name: format!("{}.{}", self.name, suffix),
+            label: self.label.as_ref().map(|l| format!("{}.{}", l, suffix)),
+            help: self.help.clone(),
+            ty: InputType::Float(values),
+            extra: BTreeMap::new(),
+        };
+        match self.ty {
+            InputType::Point2d(ref p) => ["x", "y"]
+                .iter()
+                .enumerate()
+                .map(|(i, suffix)| {
+                    let get = |v: Option<Point2d>| v.map(|v| v.to_array()[i]);
+                    let values = InputValues {
+                        default: get(p.default),
+                        min: get(p.min),
+                        max: get(p.max),
+                        identity: get(p.identity),
+                    };
+                    component(suffix, values)
+                })
+                .collect(),
+            InputType::Color(ref c) => {
+                let channels = c.channels().unwrap_or(4);
+                let get = |v: Option<Color>, i: usize| v.map(|v| v.to_array()[i]);
+                ["r", "g", "b", "a"][..channels]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, suffix)| {
+                        let values = InputValues {
+                            default: get(c.default, i),
+                            min: get(c.min, i).or(Some(0.0)),
+                            max: get(c.max, i).or(Some(1.0)),
+                            identity: get(c.identity, i),
+                        };
+                        component(suffix, values)
+                    })
+                    .collect()
+            }
+            _ => vec![self.clone()],
+        }
     }
 }
 
@@ -208,83 +899,483 @@ impl Deref for InputLong {
     }
 }
 
+impl Deref for InputPoint2d {
+    type Target = InputValues<Point2d>;
+    fn deref(&self) -> &Self::Target {
+        &self.input_values
+    }
+}
+
+impl Deref for InputColor {
+    type Target = InputValues<Color>;
+    fn deref(&self) -> &Self::Target {
+        &self.input_values
+    }
+}
+
+impl CoordinateSpace {
+    /// Convert a point specified within this space to the `target` space.
+    ///
+    /// `render_size` is the width and height of the render target in pixels.
+    pub fn convert(self, point: [f32; 2], target: Self, render_size: [f32; 2]) -> [f32; 2] {
+        let [x, y] = point;
+        let [w, h] = render_size;
+        match (self, target) {
+            (CoordinateSpace::Normalized, CoordinateSpace::Pixel) => [x * w, y * h],
+            (CoordinateSpace::Pixel, CoordinateSpace::Normalized) => [x / w, y / h],
+            _ => point,
+        }
+    }
+}
+
+impl InputValues<f32> {
+    /// The position of `value` within the `MIN` and `MAX`, where `0.0` is the `MIN` and `1.0` is
+    /// the `MAX`, e.g. for positioning a slider.
+    ///
+    /// Values outside of the bounds produce positions outside of `0.0..=1.0`. If `MIN` is greater
+    /// than `MAX` the mapping is reversed rather than corrected. If the bounds are equal, every
+    /// value is at position `0.0`. Returns `None` if either bound is absent.
+    pub fn normalize(&self, value: f32) -> Option<f32> {
+        let (min, max) = (self.min?, self.max?);
+        if min == max {
+            return Some(0.0);
+        }
+        Some((value - min) / (max - min))
+    }
+
+    /// The value at position `t` between the `MIN` and `MAX`, the inverse of
+    /// [**normalize**](#method.normalize).
+    ///
+    /// Returns `None` if either bound is absent.
+    pub fn denormalize(&self, t: f32) -> Option<f32> {
+        let (min, max) = (self.min?, self.max?);
+        Some(min + (max - min) * t)
+    }
+}
+
+impl InputColor {
+    /// Replace the `DEFAULT`.
+    pub fn with_default(mut self, default: impl Into<Color>) -> Self {
+        self.input_values.default = Some(default.into());
+        self
+    }
+
+    /// Replace the `MIN` and `MAX`.
+    pub fn with_range(mut self, min: impl Into<Color>, max: impl Into<Color>) -> Self {
+        self.input_values.min = Some(min.into());
+        self.input_values.max = Some(max.into());
+        self
+    }
+
+    /// Replace the `IDENTITY`.
+    pub fn with_identity(mut self, identity: impl Into<Color>) -> Self {
+        self.input_values.identity = Some(identity.into());
+        self
+    }
+
+    /// Replace whether the color is [**rgb**](#structfield.rgb).
+    pub fn with_rgb(mut self, rgb: bool) -> Self {
+        self.rgb = rgb;
+        self
+    }
+
+    /// The number of channels of the color.
+    ///
+    /// Returns `Some(3)` for [**rgb**](#structfield.rgb) colors or `Some(4)` for RGBA colors.
+    /// Returns `None` for RGBA colors without any values, in which case the number of channels
+    /// was never specified and consumers should assume RGBA.
+    pub fn channels(&self) -> Option<usize> {
+        let values = [self.default, self.min, self.max, self.identity];
+        match self.rgb {
+            true => Some(3),
+            false if values.iter().any(Option::is_some) => Some(4),
+            false => None,
+        }
+    }
+
+    /// The `DEFAULT` as RGBA components.
+    ///
+    /// RGB values have an alpha of `1.0`, as per [**color_to_rgba**](./fn.color_to_rgba.html).
+    pub fn default_rgba(&self) -> Option<[f32; 4]> {
+        self.default.map(|c| c.to_array())
+    }
+
+    /// The `IDENTITY` as RGBA components, as per [**default_rgba**](#method.default_rgba).
+    pub fn identity_rgba(&self) -> Option<[f32; 4]> {
+        self.identity.map(|c| c.to_array())
+    }
+}
+
+impl InputBool {
+    /// Replace the `DEFAULT`.
+    pub fn with_default(mut self, default: bool) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// The `DEFAULT`, or `false` if there is none.
+    pub fn default_or_false(&self) -> bool {
+        self.default.unwrap_or(false)
+    }
+}
+
+impl InputPoint2d {
+    /// Replace the `DEFAULT`.
+    pub fn with_default(mut self, default: impl Into<Point2d>) -> Self {
+        self.input_values.default = Some(default.into());
+        self
+    }
+
+    /// Replace the `MIN` and `MAX`.
+    pub fn with_range(mut self, min: impl Into<Point2d>, max: impl Into<Point2d>) -> Self {
+        self.input_values.min = Some(min.into());
+        self.input_values.max = Some(max.into());
+        self
+    }
+
+    /// Replace the `IDENTITY`.
+    pub fn with_identity(mut self, identity: impl Into<Point2d>) -> Self {
+        self.input_values.identity = Some(identity.into());
+        self
+    }
+
+    /// The `DEFAULT`, or the origin if there is none.
+    pub fn default_or_zero(&self) -> Point2d {
+        self.default_or(Point2d::default())
+    }
+
+    /// Replace the `COORDINATE_SPACE`.
+    pub fn with_coordinate_space(mut self, space: CoordinateSpace) -> Self {
+        self.coordinate_space = space;
+        self
+    }
+
+    /// The `DEFAULT` as an `InputValue` converted to the given space.
+    ///
+    /// `render_size` is the width and height of the render target in pixels. See
+    /// [**CoordinateSpace::convert**](./enum.CoordinateSpace.html#method.convert).
+    pub fn default_value(
+        &self,
+        space: CoordinateSpace,
+        render_size: [f32; 2],
+    ) -> Option<InputValue> {
+        let default = self.input_values.default?;
+        let point = self.coordinate_space.convert(default.to_array(), space, render_size);
+        Some(InputValue::Point2d(point))
+    }
+
+    /// The `IDENTITY` as an `InputValue` converted to the given space.
+    ///
+    /// See [**default_value**](#method.default_value).
+    pub fn identity_value(
+        &self,
+        space: CoordinateSpace,
+        render_size: [f32; 2],
+    ) -> Option<InputValue> {
+        let identity = self.input_values.identity?;
+        let point = self.coordinate_space.convert(identity.to_array(), space, render_size);
+        Some(InputValue::Point2d(point))
+    }
+}
+
+impl InputLong {
+    /// Replace the `DEFAULT`.
+    pub fn with_default(mut self, default: i32) -> Self {
+        self.input_values.default = Some(default);
+        self
+    }
+
+    /// Replace the `MIN` and `MAX`.
+    pub fn with_range(mut self, min: i32, max: i32) -> Self {
+        self.input_values.min = Some(min);
+        self.input_values.max = Some(max);
+        self
+    }
+
+    /// The index of the `DEFAULT` value within `values`, if any.
+    ///
+    /// Returns `None` if there is no default or if the default is not one of the listed values.
+    pub fn default_index(&self) -> Option<usize> {
+        let default = self.input_values.default?;
+        self.values.iter().position(|&v| v == default)
+    }
+
+    /// The `DEFAULT`, or the first of the `VALUES` if there is none.
+    ///
+    /// Returns `None` if there is neither a `DEFAULT` nor any `VALUES`.
+    pub fn default_or_first(&self) -> Option<i32> {
+        self.default.or_else(|| self.values.first().copied())
+    }
+
+    /// Whether the input describes a menu of options, i.e. it lists `VALUES`.
+    pub fn is_menu(&self) -> bool {
+        !self.values.is_empty()
+    }
+
+    /// Whether the input describes an integer slider, i.e. it is not a menu and has both a `MIN`
+    /// and `MAX`.
+    pub fn is_slider(&self) -> bool {
+        !self.is_menu() && self.min.is_some() && self.max.is_some()
+    }
+
+    /// Find the label associated with the given value.
+    ///
+    /// `values` and `labels` are parallel vectors. If they differ in length, values beyond the
+    /// length of the shorter vector have no label and `None` is returned. Such mismatches are
+    /// reported by [**Isf::validate**](./struct.Isf.html#method.validate).
+    pub fn label_for_value(&self, value: i32) -> Option<&str> {
+        let ix = self.values.iter().position(|&v| v == value)?;
+        self.labels.get(ix).map(|s| &s[..])
+    }
+
+    /// Find the value associated with the given label.
+    ///
+    /// Returns `None` for labels beyond the length of the shorter of `values` and `labels`.
+    pub fn value_for_label(&self, label: &str) -> Option<i32> {
+        let ix = self.labels.iter().position(|l| l == label)?;
+        self.values.get(ix).copied()
+    }
+
+    /// Add an option with the given value and label, or replace the label if the value is already
+    /// listed.
+    ///
+    /// If `labels` is shorter than `values`, it is first padded with the string form of each
+    /// unlabelled value so that the two remain aligned.
+    pub fn add_option(&mut self, value: i32, label: impl Into<String>) {
+        let label = label.into();
+        for v in &self.values[self.labels.len().min(self.values.len())..] {
+            self.labels.push(v.to_string());
+        }
+        match self.values.iter().position(|&v| v == value) {
+            Some(ix) => self.labels[ix] = label,
+            None => {
+                self.labels.insert(self.values.len(), label);
+                self.values.push(value);
+            }
+        }
+    }
+
+    /// Remove the option with the given value along with its label.
+    ///
+    /// If the removed value was the `DEFAULT`, the default becomes the first remaining value (or
+    /// `None` if no values remain). Returns `false` if the value was not listed.
+    pub fn remove_option(&mut self, value: i32) -> bool {
+        let ix = match self.values.iter().position(|&v| v == value) {
+            None => return false,
+            Some(ix) => ix,
+        };
+        self.values.remove(ix);
+        if ix < self.labels.len() {
+            self.labels.remove(ix);
+        }
+        if self.input_values.default == Some(value) {
+            self.input_values.default = self.values.first().copied();
+        }
+        true
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Isf {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.serialize_dict(s, false)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl SerializeDict for Isf {
+    fn serialize_dict<S>(&self, s: S, emit_null_fields: bool) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let emit = emit_null_fields;
+        let mut map = s.serialize_map(None)?;
+        let strings = [
+            ("ISFVSN", &self.isfvsn),
+            ("VSN", &self.vsn),
+            ("DESCRIPTION", &self.description),
+            ("LABEL", &self.label),
+            ("CREDIT", &self.credit),
+        ];
+        for (key, value) in &strings {
+            if value.is_some() || emit {
+                map.serialize_entry(key, value)?;
+            }
+        }
+        if !self.categories.is_empty() || emit {
+            map.serialize_entry("CATEGORIES", &self.categories)?;
+        }
+        // `INPUTS` is always written, even when empty.
+        let inputs: Vec<_> = self.inputs.iter().map(|i| Dict(i, emit)).collect();
+        map.serialize_entry("INPUTS", &inputs)?;
+        if !self.passes.is_empty() || emit {
+            let passes: Vec<_> = self.passes.iter().map(|p| Dict(p, emit)).collect();
+            map.serialize_entry("PASSES", &passes)?;
+        }
+        if !self.imported.is_empty() || emit {
+            map.serialize_entry("IMPORTED", &self.imported)?;
+        }
+        // Persistent buffers are written in the array form unless a buffer requires the dict form.
+        let buffers = &self.persistent_buffers;
+        if buffers.values().all(|b| *b == PersistentBuffer::default()) {
+            if !buffers.is_empty() {
+                let names: Vec<_> = buffers.keys().collect();
+                map.serialize_entry("PERSISTENT_BUFFERS", &names)?;
+            }
+        } else {
+            map.serialize_entry("PERSISTENT_BUFFERS", buffers)?;
+        }
+        for (key, value) in &self.extra {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "serde")]
 impl Serialize for Input {
     fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let Input { ref name, ref label, ref ty } = self;
+        self.serialize_dict(s, false)
+    }
+}
 
-        let mut dict = InputDict {
-            name: name.clone(),
-            label: label.clone(),
-            ty: String::new(),
-            default: None,
-            min: None,
-            max: None,
-            identity: None,
-            values: vec![],
-            labels: vec![],
-        };
+#[cfg(feature = "serde")]
+impl SerializeDict for Input {
+    fn serialize_dict<S>(&self, s: S, emit_null_fields: bool) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let Input { ref name, ref label, ref help, ref ty, ref extra } = self;
+        let emit = emit_null_fields;
 
-        fn pt2_to_json_value([x, y]: [f32; 2]) -> serde_json::Value {
-            serde_json::Value::Array(vec![x.into(), y.into()])
+        // Fields are written directly from the input, without an owned intermediate.
+        let mut map = s.serialize_map(None)?;
+        map.serialize_entry("NAME", name)?;
+        if label.is_some() || emit {
+            map.serialize_entry("LABEL", label)?;
+        }
+        if help.is_some() {
+            map.serialize_entry("HELP", help)?;
+        }
+        map.serialize_entry("TYPE", ty.type_str())?;
+
+        fn pt2(p: &Point2d) -> [JsonF32; 2] {
+            [JsonF32(p.x), JsonF32(p.y)]
         }
 
         match ty {
-            InputType::Event => {
-                dict.ty = "event".to_string();
+            InputType::Event | InputType::Image => {
+                serialize_values::<S::SerializeMap, ()>(&mut map, [None, None, None, None], emit)?;
             },
 
             InputType::Bool(ref t) => {
-                dict.ty = "bool".to_string();
-                dict.default = t.default.map(Into::into);
+                serialize_values(&mut map, [t.default, None, None, None], emit)?;
             },
 
             InputType::Long(ref t) => {
-                dict.ty = "long".to_string();
-                t.write_to_dict(&mut dict);
-                dict.values = t.values.clone();
-                dict.labels = t.labels.clone();
+                serialize_values(&mut map, [t.default, t.min, t.max, t.identity], emit)?;
+                if !t.values.is_empty() || emit {
+                    map.serialize_entry("VALUES", &t.values)?;
+                }
+                if !t.labels.is_empty() || emit {
+                    map.serialize_entry("LABELS", &t.labels)?;
+                }
             },
 
             InputType::Float(ref t) => {
-                dict.ty = "float".to_string();
-                t.write_to_dict(&mut dict);
+                let values = [t.default, t.min, t.max, t.identity];
+                serialize_values(&mut map, values.map(|v| v.map(JsonF32)), emit)?;
             },
 
             InputType::Point2d(ref t) => {
-                dict.ty = "point2D".to_string();
-                dict.default = t.default.map(pt2_to_json_value);
-                dict.min = t.min.map(pt2_to_json_value);
-                dict.max = t.max.map(pt2_to_json_value);
-                dict.identity = t.identity.map(pt2_to_json_value);
+                let values = [&t.default, &t.min, &t.max, &t.identity];
+                serialize_values(&mut map, values.map(|v| v.as_ref().map(pt2)), emit)?;
+                if t.coordinate_space != CoordinateSpace::Normalized {
+                    map.serialize_entry("COORDINATE_SPACE", &t.coordinate_space)?;
+                }
             },
 
             InputType::Color(ref t) => {
-                dict.ty = "color".to_string();
-                t.write_to_dict(&mut dict);
-            },
-
-            InputType::Image => {
-                dict.ty = "image".to_string();
+                let components = |c: Color| {
+                    let n = if t.rgb && c.a == 1.0 { 3 } else { 4 };
+                    c.to_array()[..n].iter().map(|&f| JsonF32(f)).collect::<Vec<_>>()
+                };
+                let values = [t.default, t.min, t.max, t.identity];
+                serialize_values(&mut map, values.map(|v| v.map(components)), emit)?;
             },
 
             InputType::Audio(ref t) => {
-                dict.ty = "audio".to_string();
-                dict.max = t.num_samples.map(Into::into);
+                serialize_values(&mut map, [None, None, t.num_samples, None], emit)?;
             },
 
             InputType::AudioFft(ref t) => {
-                dict.ty = "audioFFT".to_string();
-                dict.max = t.num_columns.map(Into::into);
+                serialize_values(&mut map, [None, None, t.num_columns, None], emit)?;
             },
         };
 
-        dict.serialize(s)
+        // Only `long` inputs have values and labels, though every input may be given them empty.
+        if emit && !matches!(ty, InputType::Long(_)) {
+            map.serialize_entry("VALUES", &[] as &[i32])?;
+            map.serialize_entry("LABELS", &[] as &[String])?;
+        }
+
+        for (key, value) in extra {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Write the `DEFAULT`, `MIN`, `MAX` and `IDENTITY` of an input, in that order.
+///
+/// Fields without a value are skipped, or written as `null` if `emit_null_fields` is set.
+#[cfg(feature = "serde")]
+fn serialize_values<M, T>(
+    map: &mut M,
+    values: [Option<T>; 4],
+    emit_null_fields: bool,
+) -> Result<(), M::Error>
+where
+    M: SerializeMap,
+    T: Serialize,
+{
+    let keys = ["DEFAULT", "MIN", "MAX", "IDENTITY"];
+    for (key, value) in keys.iter().zip(&values) {
+        if value.is_some() || emit_null_fields {
+            map.serialize_entry(key, value)?;
+        }
     }
+    Ok(())
 }
 
+/// Serializes an `f32` widened to an `f64`, or as a unit if not finite.
+///
+/// This matches the conversion of an `f32` to a `serde_json::Value`, ensuring the written digits
+/// parse back to the same `f32`.
+#[cfg(feature = "serde")]
+struct JsonF32(f32);
+
+#[cfg(feature = "serde")]
+impl Serialize for JsonF32 {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.0.is_finite() {
+            s.serialize_f64(f64::from(self.0))
+        } else {
+            s.serialize_unit()
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for Input {
     fn deserialize<D>(d: D) -> Result<Self, D::Error>
     where
@@ -293,6 +1384,7 @@ impl<'de> Deserialize<'de> for Input {
         let InputDict {
             name,
             label,
+            help,
             ty,
             default,
             min,
@@ -300,23 +1392,16 @@ impl<'de> Deserialize<'de> for Input {
             identity,
             values,
             labels,
+            coordinate_space,
+            extra,
         } = InputDict::deserialize(d)?;
 
-        let ty = match &ty[..] {
+        let ty = match InputType::canonical_type_str(&ty).unwrap_or_default() {
             "event" => InputType::Event,
 
             "bool" => InputType::Bool(InputBool {
                 default: match default {
-                    Some(serde_json::Value::Bool(b)) => Some(b),
-                    Some(serde_json::Value::Number(n)) if n.is_u64() => {
-                        Some(n.as_u64().unwrap() != 0)
-                    }
-                    Some(serde_json::Value::Number(n)) if n.is_f64() => {
-                        Some(n.as_f64().unwrap() as u64 != 0)
-                    }
-                    Some(value) => {
-                        serde_json::from_value(value).map_err(serde::de::Error::custom)?
-                    }
+                    Some(value) => Some(deserialize_bool(value).map_err(serde::de::Error::custom)?),
                     None => None,
                 },
             }),
@@ -333,67 +1418,476 @@ impl<'de> Deserialize<'de> for Input {
                     .map_err(serde::de::Error::custom)?,
             ),
 
-            "point2D" => InputType::Point2d(
-                InputPoint2d::from_opts(default, min, max, identity)
+            "point2D" => InputType::Point2d(InputPoint2d {
+                input_values: InputValues::from_opts(default, min, max, identity)
                     .map_err(serde::de::Error::custom)?,
-            ),
+                coordinate_space: coordinate_space.unwrap_or_default(),
+            }),
 
-            "color" => InputType::Color(
-                InputColor::from_opts(default, min, max, identity)
-                    .map_err(serde::de::Error::custom)?,
-            ),
+            "color" => {
+                // Values may be written as hex strings, which are normalized to arrays.
+                let values = InputValues::<ColorComponents>::from_opts(default, min, max, identity)
+                    .map_err(serde::de::Error::custom)?;
+                let components = [&values.default, &values.min, &values.max, &values.identity];
+                let mut lens = components.iter().filter_map(|c| c.as_ref().map(|c| c.0.len()));
+                let rgb = lens.next() == Some(3) && lens.all(|len| len == 3);
+                let color = |c: Option<ColorComponents>| match c {
+                    None => Ok(None),
+                    Some(c) => color_to_rgba(&c.0).map(|rgba| Some(Color::from(rgba))),
+                };
+                let input_values = InputValues {
+                    default: color(values.default).map_err(serde::de::Error::custom)?,
+                    min: color(values.min).map_err(serde::de::Error::custom)?,
+                    max: color(values.max).map_err(serde::de::Error::custom)?,
+                    identity: color(values.identity).map_err(serde::de::Error::custom)?,
+                };
+                InputType::Color(InputColor { input_values, rgb })
+            },
 
             "image" => InputType::Image,
 
             "audio" => InputType::Audio(InputAudio {
-                num_samples: match max {
-                    Some(value) => {
-                        serde_json::from_value(value).map_err(serde::de::Error::custom)?
-                    }
-                    None => None,
-                },
+                num_samples: from_opt_value(max).map_err(serde::de::Error::custom)?,
             }),
 
             "audioFFT" => InputType::AudioFft(InputAudioFft {
-                num_columns: match max {
-                    Some(value) => {
-                        serde_json::from_value(value).map_err(serde::de::Error::custom)?
-                    }
-                    None => None,
-                },
+                num_columns: from_opt_value(max).map_err(serde::de::Error::custom)?,
             }),
 
-            _ => unimplemented!(), // TODO: Return serde err "unknown type".
+            _ => {
+                let err = UnknownInputType { ty };
+                return Err(serde::de::Error::custom(format!("input \"{}\": {}", name, err)));
+            }
         };
 
-        Ok(Input { name, label, ty })
+        Ok(Input { name, label, help, ty, extra })
     }
 }
 
+#[cfg(feature = "serde")]
+impl Serialize for Pass {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.serialize_dict(s, false)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl SerializeDict for Pass {
+    fn serialize_dict<S>(&self, s: S, emit_null_fields: bool) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let emit = emit_null_fields;
+        let width = self.width.as_ref().map(|w| JsonDimension(w, self.width_is_number));
+        let height = self.height.as_ref().map(|h| JsonDimension(h, self.height_is_number));
+        let mut map = s.serialize_map(None)?;
+        if self.target.is_some() || emit {
+            map.serialize_entry("TARGET", &self.target)?;
+        }
+        if self.persistent || emit {
+            map.serialize_entry("PERSISTENT", &self.persistent)?;
+        }
+        if self.float || emit {
+            map.serialize_entry("FLOAT", &self.float)?;
+        }
+        if width.is_some() || emit {
+            map.serialize_entry("WIDTH", &width)?;
+        }
+        if height.is_some() || emit {
+            map.serialize_entry("HEIGHT", &height)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Pass {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let PassDict {
+            target,
+            persistent,
+            float,
+            width,
+            height,
+        } = PassDict::deserialize(d)?;
+        let (width, width_is_number) = dimension(width).map_err(serde::de::Error::custom)?;
+        let (height, height_is_number) = dimension(height).map_err(serde::de::Error::custom)?;
+        Ok(Pass {
+            target,
+            persistent,
+            float,
+            width,
+            height,
+            width_is_number,
+            height_is_number,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for PersistentBuffer {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let width = self.width.as_ref().map(|w| JsonDimension(w, self.width_is_number));
+        let height = self.height.as_ref().map(|h| JsonDimension(h, self.height_is_number));
+        let mut map = s.serialize_map(Some(3))?;
+        map.serialize_entry("FLOAT", &self.float)?;
+        map.serialize_entry("WIDTH", &width)?;
+        map.serialize_entry("HEIGHT", &height)?;
+        map.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PersistentBuffer {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let PersistentBufferDict {
+            float,
+            width,
+            height,
+        } = PersistentBufferDict::deserialize(d)?;
+        let (width, width_is_number) = dimension(width).map_err(serde::de::Error::custom)?;
+        let (height, height_is_number) = dimension(height).map_err(serde::de::Error::custom)?;
+        Ok(PersistentBuffer {
+            float,
+            width,
+            height,
+            width_is_number,
+            height_is_number,
+        })
+    }
+}
+
+/// Serializes a `WIDTH` or `HEIGHT` as a JSON number if it was written as one, or otherwise as a
+/// string.
+#[cfg(feature = "serde")]
+struct JsonDimension<'a>(&'a str, bool);
+
+#[cfg(feature = "serde")]
+impl<'a> Serialize for JsonDimension<'a> {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Fall back to the string should the dimension have since become an expression.
+        match self.0.parse::<serde_json::Number>() {
+            Ok(n) if self.1 => n.serialize(s),
+            _ => s.serialize_str(self.0),
+        }
+    }
+}
+
+/// A `WIDTH` or `HEIGHT` as a string, along with whether it was written as a JSON number.
+#[cfg(feature = "serde")]
+fn dimension(
+    value: Option<serde_json::Value>,
+) -> Result<(Option<String>, bool), serde_json::Error> {
+    let is_number = value.as_ref().is_some_and(|v| v.is_number());
+    let dim = deserialize_opt_string(value.unwrap_or(serde_json::Value::Null))?;
+    Ok((dim, is_number))
+}
+
 /// Attempt to parse an ISF blob from a GLSL source string.
 ///
 /// This will not do any GLSL parsing and simply checks the top of the string for a `/* */` comment
-/// containing JSON that may be parsed as an ISF blob.
+/// containing JSON that may be parsed as an ISF blob. A leading `#version` directive, `//`
+/// comments and `/* */` comments that do not begin with a `{` are skipped.
+#[cfg(feature = "serde")]
 pub fn parse(glsl_src: &str) -> Result<Isf, ParseError> {
     let comment_contents = top_comment_contents(glsl_src).ok_or(ParseError::MissingTopComment)?;
-    Ok(serde_json::from_str(comment_contents)?)
+    serde_json::from_str(comment_contents)
+        .map_err(|err| ParseError::json(err, glsl_src, comment_contents))
+}
+
+/// Attempt to parse an ISF blob from a GLSL source string, also returning the GLSL that follows
+/// the ISF comment.
+///
+/// The returned [**Body**](./struct.Body.html) is an exact slice of `glsl_src`, starting directly
+/// after the closing `*/`. No whitespace is trimmed, so that positions within the body can be
+/// mapped back to positions within the original source (e.g. when reporting compile errors).
+#[cfg(feature = "serde")]
+pub fn parse_with_source(glsl_src: &str) -> Result<(Isf, Body<'_>), ParseError> {
+    let range = find_comment(glsl_src).ok_or(ParseError::MissingTopComment)?;
+    let json = glsl_src[range.clone()].trim();
+    let isf = serde_json::from_str(json).map_err(|err| ParseError::json(err, glsl_src, json))?;
+    // Skip the closing delimiter, including the `!` of a `!*/` marker.
+    let offset = range.end + glsl_src[range.end..].find("*/").unwrap_or_default() + "*/".len();
+    let line = 1 + glsl_src[..offset].matches('\n').count();
+    let body = Body {
+        src: &glsl_src[offset..],
+        offset,
+        line,
+    };
+    Ok((isf, body))
+}
+
+/// Attempt to parse an ISF blob from a GLSL source string, also returning the GLSL that follows
+/// the closing `*/` of the ISF comment.
+///
+/// This is a shorthand for [**parse_with_source**](./fn.parse_with_source.html) for callers that
+/// do not require the position of the body within the source.
+#[cfg(feature = "serde")]
+pub fn parse_with_body(glsl_src: &str) -> Result<(Isf, &str), ParseError> {
+    parse_with_source(glsl_src).map(|(isf, body)| (isf, body.src))
+}
+
+/// Read the GLSL source file at the given path and attempt to parse an ISF blob from it.
+///
+/// Failure to read the file is reported via the `ParseError::Io` variant, allowing callers to
+/// distinguish IO errors from errors in the file's contents.
+#[cfg(feature = "serde")]
+pub fn parse_file(path: impl AsRef<Path>) -> Result<Isf, ParseError> {
+    let path = path.as_ref();
+    let glsl_src = std::fs::read_to_string(path).map_err(|err| ParseError::Io {
+        path: path.to_path_buf(),
+        err,
+    })?;
+    parse(&glsl_src)
+}
+
+/// Attempt to parse an ISF blob from a GLSL source string, applying the given transform to each
+/// `Input` in order.
+///
+/// This provides an extension point for import pipelines, e.g. to normalize input names or clamp
+/// defaults across a library of shaders. Inputs are transformed by value so that no clones are
+/// required.
+#[cfg(feature = "serde")]
+pub fn parse_with_transform<F>(glsl_src: &str, transform: F) -> Result<Isf, ParseError>
+where
+    F: FnMut(Input) -> Input,
+{
+    let mut isf = parse(glsl_src)?;
+    isf.inputs = isf.inputs.into_iter().map(transform).collect();
+    Ok(isf)
+}
+
+/// Cheaply check whether the GLSL source string appears to contain an ISF blob.
+///
+/// This checks that the top `/* */` comment contains a `{ ... }` block, without parsing the JSON
+/// or allocating. This is useful for filtering files prior to calling [**parse**](./fn.parse.html)
+/// (which may still fail if the JSON is malformed).
+pub fn contains_isf(glsl_src: &str) -> bool {
+    top_comment_contents(glsl_src).is_some_and(|c| c.starts_with('{') && c.ends_with('}'))
 }
 
 /// Find the top `/* */` comment in a GLSL src string and return the contents with whitespace
 /// trimmed.
 fn top_comment_contents(glsl_src: &str) -> Option<&str> {
-    let start = glsl_src.find("/*")? + "/*".len();
-    let end = start + glsl_src[start..].find("*/")?;
-    Some(glsl_src[start..end].trim())
+    find_comment(glsl_src).map(|range| glsl_src[range].trim())
+}
+
+/// Find the top `/* */` comment in a GLSL src string and return the byte range of its contents.
+///
+/// The top comment is the first `/* */` comment whose contents begin with a JSON object or data
+/// URI. Any preceding code, such as a `#version` directive, and other comments, such as a license
+/// header, are skipped. `/*` sequences within `//` line comments do not open a comment.
+///
+/// The range spans everything between the `/*` and `*/` delimiters, including any surrounding
+/// whitespace, allowing editors to replace the ISF blob without disturbing the rest of the
+/// source. The `!` markers of a `/*! !*/` doc comment are excluded from the range. JSON can
+/// neither begin nor end with a `!`, so these are never part of the ISF blob.
+pub fn find_comment(glsl_src: &str) -> Option<Range<usize>> {
+    let mut pos = 0;
+    while let Some(ix) = glsl_src[pos..].find('/') {
+        let start = pos + ix;
+        let rest = &glsl_src[start..];
+        if rest.starts_with("//") {
+            pos = start + rest.find('\n')?;
+        } else if rest.starts_with("/*") {
+            let contents = start + "/*".len();
+            let end = block_comment_end(&glsl_src[contents..], "*/")?;
+            let mut range = contents..contents + end;
+            pos = range.end + "*/".len();
+            if glsl_src[range.clone()].starts_with('!') {
+                range.start += 1;
+            }
+            if glsl_src[range.clone()].ends_with('!') {
+                range.end -= 1;
+            }
+            if is_isf_blob(glsl_src[range.clone()].trim()) {
+                return Some(range);
+            }
+        } else {
+            pos = start + 1;
+        }
+    }
+    None
+}
+
+/// Find the `close` delimiter (e.g. `*/`) of the comment whose contents begin at the start of
+/// `src`.
+///
+/// If the contents begin with a JSON object, any `close` within a JSON string is skipped so that
+/// e.g. a `DESCRIPTION` may contain the sequence. Should the JSON contain an unterminated string,
+/// the first `close` is used so that the error is reported by the JSON parser.
+fn block_comment_end(src: &str, close: &str) -> Option<usize> {
+    if !src.trim_start_matches('!').trim_start().starts_with('{') {
+        return src.find(close);
+    }
+    let mut in_string = false;
+    let mut escaped = false;
+    for (ix, ch) in src.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if in_string {
+            match ch {
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => (),
+            }
+        } else if ch == '"' {
+            in_string = true;
+        } else if src[ix..].starts_with(close) {
+            return Some(ix);
+        }
+    }
+    src.find(close)
 }
 
-/// Support integers for bool seriallization.
+/// Whether the trimmed contents of a comment may contain an ISF blob, i.e. a JSON object or a
+/// JSON data URI.
+fn is_isf_blob(contents: &str) -> bool {
+    contents.starts_with('{') || contents.starts_with("data:application/json")
+}
+
+/// Find the first comment delimited by `open` and `close` that may contain an ISF blob and return
+/// the byte range of its contents.
+///
+/// The default `/* */` delimiters are located via [**find_comment**](./fn.find_comment.html).
+/// Otherwise, comments are skipped and `close` sequences within JSON strings are ignored in the
+/// same manner.
+#[cfg(feature = "serde")]
+fn comment_range(src: &str, open: &str, close: &str) -> Option<Range<usize>> {
+    if open == "/*" && close == "*/" {
+        return find_comment(src);
+    }
+    let mut pos = 0;
+    while let Some(ix) = src[pos..].find(open) {
+        let start = pos + ix + open.len();
+        let end = start + block_comment_end(&src[start..], close)?;
+        if is_isf_blob(src[start..end].trim()) {
+            return Some(start..end);
+        }
+        // Empty delimiters would otherwise never advance.
+        if end + close.len() == pos {
+            break;
+        }
+        pos = end + close.len();
+    }
+    None
+}
+
+/// The 1-based line and column of the start of `slice` within `src`, of which it must be a slice.
+#[cfg(feature = "serde")]
+fn source_position(src: &str, slice: &str) -> (usize, usize) {
+    let offset = slice.as_ptr() as usize - src.as_ptr() as usize;
+    let line_start = src[..offset].rfind('\n').map_or(0, |ix| ix + 1);
+    let line = 1 + src[..offset].matches('\n').count();
+    (line, offset - line_start + 1)
+}
+
+/// Normalize a category name for comparison.
+fn normalize_category(cat: &str) -> String {
+    cat.trim().to_lowercase()
+}
+
+/// Support both the array and dict forms of the v1 `PERSISTENT_BUFFERS`.
+#[cfg(feature = "serde")]
+fn deserialize_persistent_buffers<'de, D>(
+    d: D,
+) -> Result<BTreeMap<String, PersistentBuffer>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Buffers {
+        Names(Vec<String>),
+        Dict(BTreeMap<String, PersistentBuffer>),
+    }
+    let buffers = match Buffers::deserialize(d)? {
+        Buffers::Names(names) => names.into_iter().map(|n| (n, Default::default())).collect(),
+        Buffers::Dict(dict) => dict,
+    };
+    Ok(buffers)
+}
+
+/// Deserialize imports from either the dict form or an array of named imports.
+#[cfg(feature = "serde")]
+fn deserialize_imported<'de, D>(d: D) -> Result<BTreeMap<String, ImageImport>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct NamedImport {
+        #[serde(rename = "NAME")]
+        name: String,
+        #[serde(flatten)]
+        import: ImageImport,
+    }
+
+    // Branch on the form directly rather than via an untagged enum, so that errors within an
+    // import (e.g. a missing `PATH`) are reported as-is.
+    struct ImportsVisitor;
+
+    impl<'de> Visitor<'de> for ImportsVisitor {
+        type Value = BTreeMap<String, ImageImport>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "a dict of imports or an array of named imports")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut imports = BTreeMap::new();
+            while let Some(NamedImport { name, import }) = seq.next_element()? {
+                imports.insert(name, import);
+            }
+            Ok(imports)
+        }
+
+        fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            BTreeMap::deserialize(MapAccessDeserializer::new(map))
+        }
+    }
+
+    d.deserialize_any(ImportsVisitor)
+}
+
+/// A bool deserialization that also supports numbers and the strings `"YES"`, `"NO"`, `"true"`
+/// and `"false"`, compared case-insensitively.
+#[cfg(feature = "serde")]
 fn deserialize_bool<'de, D>(d: D) -> Result<bool, D::Error>
 where
     D: Deserializer<'de>,
 {
     let b = match serde_json::Value::deserialize(d)? {
         serde_json::Value::Bool(b) => b,
+        serde_json::Value::String(s) => match &s.to_ascii_lowercase()[..] {
+            "yes" | "true" => true,
+            "no" | "false" => false,
+            _ => {
+                let expected = "expected one of: YES, NO, true, false";
+                let msg = format!("invalid boolean \"{}\", {}", s, expected);
+                return Err(serde::de::Error::custom(msg));
+            }
+        },
         serde_json::Value::Number(n) if n.is_u64() => {
             n.as_u64().unwrap() != 0
         }
@@ -405,7 +1899,20 @@ where
     Ok(b)
 }
 
+/// Deserialize an optional value buffered by the `Input` deserializer.
+///
+/// The buffered value is consumed, so no strings or arrays are cloned. Buffering is required as
+/// the `TYPE` determining the value's type may follow the value within the input's dict.
+#[cfg(feature = "serde")]
+fn from_opt_value<T>(value: Option<serde_json::Value>) -> Result<Option<T>, serde_json::Error>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    value.map(T::deserialize).transpose()
+}
+
 /// A string deserialization that also supports integers.
+#[cfg(feature = "serde")]
 fn deserialize_opt_string<'de, D>(d: D) -> Result<Option<String>, D::Error>
 where
     D: Deserializer<'de>,
@@ -0,0 +1,191 @@
+//! Checks for likely mistakes within ISF shaders that are not strictly errors.
+
+#[cfg(feature = "serde")]
+use crate::{top_comment_contents, InputType, ParseError};
+use crate::{glsl, Isf};
+use std::fmt;
+
+/// A likely mistake within an ISF shader.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Warning {
+    /// The shader declares multiple `PASSES` but the body never reads `PASSINDEX`, so every pass
+    /// executes identical code.
+    PassIndexUnused { passes: usize },
+    /// The `LABEL` of the input contains control characters such as newlines or tabs, which are
+    /// likely to break the layout of a UI.
+    LabelControlChars { input: String },
+    /// The `LABEL` of the input is longer than the `max_label_len` in characters.
+    LabelTooLong {
+        input: String,
+        len: usize,
+        max: usize,
+    },
+    /// A number within the `DEFAULT`, `MIN`, `MAX` or `IDENTITY` of the input cannot be
+    /// represented exactly by the `f32` in which it is stored.
+    PrecisionLoss {
+        input: String,
+        /// The key of the field containing the number, e.g. `DEFAULT`.
+        field: String,
+        /// The number as written within the JSON.
+        literal: f64,
+        /// The number as stored.
+        stored: f32,
+    },
+}
+
+/// Options for the checks performed by [**Isf::lint**](./struct.Isf.html#method.lint).
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintOptions {
+    /// The maximum number of characters within an input's `LABEL`.
+    pub max_label_len: usize,
+}
+
+/// The warnings produced by [**Isf::lint**](./struct.Isf.html#method.lint).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LintReport {
+    pub warnings: Vec<Warning>,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        LintOptions { max_label_len: 32 }
+    }
+}
+
+impl LintReport {
+    /// Whether or not the shader passed all checks.
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Warning::PassIndexUnused { passes } => write!(
+                f,
+                "the shader declares {} passes but never reads `PASSINDEX`",
+                passes
+            ),
+            Warning::LabelControlChars { ref input } => write!(
+                f,
+                "the label of input `{}` contains control characters",
+                input
+            ),
+            Warning::LabelTooLong {
+                ref input,
+                len,
+                max,
+            } => write!(
+                f,
+                "the label of input `{}` is {} characters long, exceeding the maximum of {}",
+                input, len, max
+            ),
+            Warning::PrecisionLoss {
+                ref input,
+                ref field,
+                literal,
+                stored,
+            } => write!(
+                f,
+                "the {} of input `{}` is written as {} but stored as {}",
+                field,
+                input,
+                literal,
+                f64::from(stored)
+            ),
+        }
+    }
+}
+
+/// Attempt to parse an ISF blob from a GLSL source string, also returning warnings about values
+/// that are not preserved by parsing.
+///
+/// Currently a [**Warning::PrecisionLoss**](./enum.Warning.html#variant.PrecisionLoss) is
+/// produced for each number within the values of a `float`, `point2D` or `color` input that does
+/// not survive the conversion to `f32`, e.g. `0.1`. Such a number would be written differently
+/// if the `Isf` were serialized again, e.g. as `0.10000000149011612`.
+#[cfg(feature = "serde")]
+pub fn parse_verbose(glsl_src: &str) -> Result<(Isf, Vec<Warning>), ParseError> {
+    let comment_contents = top_comment_contents(glsl_src).ok_or(ParseError::MissingTopComment)?;
+    let json_err = |err| ParseError::json(err, glsl_src, comment_contents);
+    let value: serde_json::Value = serde_json::from_str(comment_contents).map_err(json_err)?;
+    let mut warnings = vec![];
+    let inputs = value.get("INPUTS").and_then(|i| i.as_array());
+    for input in inputs.into_iter().flatten() {
+        let name = input
+            .get("NAME")
+            .and_then(|n| n.as_str())
+            .unwrap_or_default();
+        let ty = input.get("TYPE").and_then(|t| t.as_str());
+        match ty.and_then(InputType::canonical_type_str) {
+            Some("float") | Some("point2D") | Some("color") => (),
+            _ => continue,
+        }
+        for field in &["DEFAULT", "MIN", "MAX", "IDENTITY"] {
+            let numbers = match input.get(field) {
+                Some(serde_json::Value::Array(arr)) => arr.iter().collect(),
+                Some(value) => vec![value],
+                None => vec![],
+            };
+            for literal in numbers.into_iter().filter_map(|n| n.as_f64()) {
+                let stored = literal as f32;
+                // Values are serialized as the `f32` widened to an `f64`.
+                if f64::from(stored) != literal {
+                    warnings.push(Warning::PrecisionLoss {
+                        input: name.to_string(),
+                        field: field.to_string(),
+                        literal,
+                        stored,
+                    });
+                }
+            }
+        }
+    }
+    // Parse from the source again so that errors are reported with their position.
+    let isf = serde_json::from_str(comment_contents).map_err(json_err)?;
+    Ok((isf, warnings))
+}
+
+impl Isf {
+    /// Check the shader's metadata for likely presentation issues.
+    ///
+    /// Currently this checks that each input's `LABEL` contains no control characters and does
+    /// not exceed `options.max_label_len` characters. Checks requiring the shader body, such as
+    /// [**check_passindex_usage**](#method.check_passindex_usage), are performed separately.
+    pub fn lint(&self, options: &LintOptions) -> LintReport {
+        let mut warnings = vec![];
+        for input in &self.inputs {
+            let label = match input.label {
+                None => continue,
+                Some(ref label) => label,
+            };
+            if label.chars().any(char::is_control) {
+                warnings.push(Warning::LabelControlChars {
+                    input: input.name.clone(),
+                });
+            }
+            let len = label.chars().count();
+            if len > options.max_label_len {
+                warnings.push(Warning::LabelTooLong {
+                    input: input.name.clone(),
+                    len,
+                    max: options.max_label_len,
+                });
+            }
+        }
+        LintReport { warnings }
+    }
+
+    /// Check that a multi-pass shader branches on `PASSINDEX`.
+    ///
+    /// ISF executes the same body once per pass. If `PASSES` has multiple entries but the body
+    /// never reads `PASSINDEX`, every pass runs identical code, which is almost certainly a bug.
+    pub fn check_passindex_usage(&self, body: &str) -> Option<Warning> {
+        let passes = self.passes.len();
+        if passes > 1 && !glsl::references(body, "PASSINDEX") {
+            return Some(Warning::PassIndexUnused { passes });
+        }
+        None
+    }
+}
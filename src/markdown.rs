@@ -0,0 +1,110 @@
+//! Generation of markdown documentation for ISF shaders.
+
+use crate::{Input, InputType, Isf};
+use std::fmt::{Display, Write};
+
+impl Isf {
+    /// Produce markdown documenting the shader's parameters, e.g. for a shader catalog.
+    ///
+    /// The output consists of a heading with the shader's [**title**](#method.title) (if any),
+    /// the `DESCRIPTION`, the `CATEGORIES` and a table with a row per input listing its name,
+    /// type, range, default and label. The range of a `long` input lists its `VALUES` along with
+    /// their `LABELS`. Empty cells are written as `-`.
+    ///
+    /// The format is stable, so the output is suitable for snapshot testing.
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        if let Some(title) = self.title() {
+            writeln!(md, "# {}\n", title).unwrap();
+        }
+        if let Some(ref description) = self.description {
+            writeln!(md, "{}\n", description.trim()).unwrap();
+        }
+        if !self.categories.is_empty() {
+            writeln!(md, "**Categories:** {}\n", self.categories.join(", ")).unwrap();
+        }
+        if self.inputs.is_empty() {
+            md.push_str("This shader has no inputs.\n");
+            return md;
+        }
+        md.push_str("| Name | Type | Range | Default | Label |\n");
+        md.push_str("| --- | --- | --- | --- | --- |\n");
+        for input in &self.inputs {
+            let (range, default) = range_and_default(input);
+            let label = input.label.as_ref().map(|l| cell(l)).unwrap_or_default();
+            writeln!(
+                md,
+                "| `{}` | {} | {} | {} | {} |",
+                cell(&input.name),
+                input.ty.type_str(),
+                or_dash(range),
+                or_dash(default),
+                or_dash(label),
+            )
+            .unwrap();
+        }
+        md
+    }
+}
+
+/// The contents of the range and default cells for the input.
+fn range_and_default(input: &Input) -> (String, String) {
+    fn range<T: Display>(min: Option<T>, max: Option<T>) -> String {
+        match (min, max) {
+            (Some(min), Some(max)) => format!("{} to {}", min, max),
+            (Some(min), None) => format!(">= {}", min),
+            (None, Some(max)) => format!("<= {}", max),
+            (None, None) => String::new(),
+        }
+    }
+    fn opt<T: Display>(t: Option<T>) -> String {
+        t.map(|t| t.to_string()).unwrap_or_default()
+    }
+    fn list(values: &[f32]) -> String {
+        let values: Vec<_> = values.iter().map(ToString::to_string).collect();
+        format!("[{}]", values.join(", "))
+    }
+    match input.ty {
+        InputType::Bool(ref b) => (String::new(), opt(b.default)),
+        InputType::Long(ref l) if !l.values.is_empty() => {
+            let options: Vec<_> = l
+                .values
+                .iter()
+                .enumerate()
+                .map(|(ix, v)| match l.labels.get(ix) {
+                    Some(label) => format!("{} ({})", v, cell(label)),
+                    None => v.to_string(),
+                })
+                .collect();
+            (options.join(", "), opt(l.default))
+        }
+        InputType::Long(ref l) => (range(l.min, l.max), opt(l.default)),
+        InputType::Float(ref f) => (range(f.min, f.max), opt(f.default)),
+        InputType::Point2d(ref p) => {
+            let min = p.min.map(|p| list(&p.to_array()));
+            let max = p.max.map(|p| list(&p.to_array()));
+            (range(min, max), opt(p.default.map(|p| list(&p.to_array()))))
+        }
+        InputType::Color(ref c) => {
+            let min = c.min.map(|c| list(&c.to_array()));
+            let max = c.max.map(|c| list(&c.to_array()));
+            (range(min, max), opt(c.default.map(|c| list(&c.to_array()))))
+        }
+        InputType::Audio(ref a) => (range(None, a.num_samples), String::new()),
+        InputType::AudioFft(ref a) => (range(None, a.num_columns), String::new()),
+        InputType::Event | InputType::Image => (String::new(), String::new()),
+    }
+}
+
+/// Escape text for use within a table cell.
+fn cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+fn or_dash(s: String) -> String {
+    if s.is_empty() {
+        "-".to_string()
+    } else {
+        s
+    }
+}
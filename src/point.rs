@@ -0,0 +1,76 @@
+//! A typed representation of ISF `point2D` values.
+
+use crate::InputValue;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A two-dimensional point, e.g. the value of a `point2D` input.
+///
+/// Points are written as an array of exactly two components, `[x, y]`. Deserializing an array
+/// of any other length is an error.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Point2d {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point2d {
+    /// A point with the given components.
+    pub const fn new(x: f32, y: f32) -> Self {
+        Point2d { x, y }
+    }
+
+    /// A point from an `[x, y]` array.
+    pub const fn from_array([x, y]: [f32; 2]) -> Self {
+        Point2d { x, y }
+    }
+
+    /// The components as an `[x, y]` array.
+    pub fn to_array(&self) -> [f32; 2] {
+        [self.x, self.y]
+    }
+}
+
+impl From<[f32; 2]> for Point2d {
+    fn from(array: [f32; 2]) -> Self {
+        Point2d::from_array(array)
+    }
+}
+
+impl From<Point2d> for [f32; 2] {
+    fn from(point: Point2d) -> Self {
+        point.to_array()
+    }
+}
+
+impl From<Point2d> for InputValue {
+    fn from(point: Point2d) -> Self {
+        InputValue::Point2d(point.to_array())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Point2d {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_array().serialize(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Point2d {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Vec::<f32>::deserialize(d)?[..] {
+            [x, y] => Ok(Point2d { x, y }),
+            ref components => Err(serde::de::Error::custom(format!(
+                "expected a point with 2 components, found {}",
+                components.len()
+            ))),
+        }
+    }
+}
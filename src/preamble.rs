@@ -0,0 +1,92 @@
+//! Generation of the declarations that ISF hosts inject ahead of the shader body.
+
+/// The GLSL dialects for which a preamble may be generated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GlslVersion {
+    /// GLSL ES 1.00, as used by WebGL 1 and OpenGL ES 2.
+    Es100,
+    /// GLSL ES 3.00, as used by WebGL 2 and OpenGL ES 3.
+    Es300,
+    /// Desktop GLSL 3.30, as used by OpenGL 3.3 core.
+    Glsl330,
+}
+
+impl GlslVersion {
+    /// The `#version` directive for the dialect.
+    pub fn directive(&self) -> &'static str {
+        match *self {
+            GlslVersion::Es100 => "#version 100",
+            GlslVersion::Es300 => "#version 300 es",
+            GlslVersion::Glsl330 => "#version 330",
+        }
+    }
+
+    /// Whether or not the dialect is a GLSL ES dialect, requiring default precision qualifiers.
+    pub fn is_es(&self) -> bool {
+        match *self {
+            GlslVersion::Es100 | GlslVersion::Es300 => true,
+            GlslVersion::Glsl330 => false,
+        }
+    }
+}
+
+/// Produce the declarations that an ISF host provides to a fragment shader, allowing the shader
+/// body to be compiled against a plain GLSL target.
+///
+/// The preamble begins with the `#version` directive and declares:
+///
+/// - the `PASSINDEX`, `RENDERSIZE`, `TIME`, `TIMEDELTA`, `DATE` and `FRAMEINDEX` uniforms,
+/// - the `isf_FragNormCoord` input from the vertex stage,
+/// - for GLSL ES 3.00 and GLSL 3.30, the `isf_FragColor` output along with `gl_FragColor` and
+///   `texture2D` macros mapping the GLSL ES 1.00 names used by ISF bodies onto it and `texture`,
+///   and
+/// - the `IMG_PIXEL`, `IMG_NORM_PIXEL`, `IMG_THIS_PIXEL`, `IMG_THIS_NORM_PIXEL` and `IMG_SIZE`
+///   macros.
+///
+/// The declarations of the shader's own inputs are not included. See
+/// [**Isf::uniform_declarations**](./struct.Isf.html#method.uniform_declarations).
+///
+/// GLSL ES 1.00 cannot query the size of a texture, so `IMG_SIZE` (and in turn `IMG_PIXEL`)
+/// assumes that every image matches the `RENDERSIZE` for that dialect.
+pub fn standard_preamble(version: GlslVersion) -> String {
+    let mut src = String::new();
+    src.push_str(version.directive());
+    src.push('\n');
+    if version.is_es() {
+        src.push_str("precision highp float;\nprecision highp int;\n");
+    }
+    src.push_str(
+        "\
+uniform int PASSINDEX;
+uniform vec2 RENDERSIZE;
+uniform float TIME;
+uniform float TIMEDELTA;
+uniform vec4 DATE;
+uniform int FRAMEINDEX;
+",
+    );
+    let (input, texture, size) = match version {
+        GlslVersion::Es100 => ("varying", "texture2D", "RENDERSIZE"),
+        GlslVersion::Es300 | GlslVersion::Glsl330 => {
+            ("in", "texture", "vec2(textureSize(image, 0))")
+        }
+    };
+    src.push_str(&format!("{} vec2 isf_FragNormCoord;\n", input));
+    if version != GlslVersion::Es100 {
+        // ISF bodies write `gl_FragColor` and sample via `texture2D`, neither of which exist here.
+        src.push_str("out vec4 isf_FragColor;\n");
+        src.push_str("#define gl_FragColor isf_FragColor\n");
+        src.push_str("#define texture2D texture\n");
+    }
+    src.push_str(&format!("#define IMG_SIZE(image) {}\n", size));
+    src.push_str(&format!(
+        "#define IMG_NORM_PIXEL(image, coord) {}(image, coord)\n",
+        texture
+    ));
+    src.push_str(
+        "#define IMG_PIXEL(image, coord) IMG_NORM_PIXEL(image, (coord) / IMG_SIZE(image))\n",
+    );
+    src.push_str("#define IMG_THIS_NORM_PIXEL(image) IMG_NORM_PIXEL(image, isf_FragNormCoord)\n");
+    src.push_str("#define IMG_THIS_PIXEL(image) IMG_THIS_NORM_PIXEL(image)\n");
+    src
+}
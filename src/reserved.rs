@@ -0,0 +1,32 @@
+//! The names reserved by ISF for the declarations that hosts inject into the shader.
+
+/// The names of the uniforms, variables and macros that ISF hosts declare, which must not be used
+/// by the shader's own declarations (e.g. input names).
+///
+/// `inputImage` is included as the spec reserves it for the `image` input of filters. Such an
+/// input is not a collision.
+pub const RESERVED_NAMES: &[&str] = &[
+    "PASSINDEX",
+    "RENDERSIZE",
+    "TIME",
+    "TIMEDELTA",
+    "DATE",
+    "FRAMEINDEX",
+    "inputImage",
+    "isf_FragNormCoord",
+    "vv_FragNormCoord",
+    "isf_FragColor",
+    "texture2D",
+    "IMG_PIXEL",
+    "IMG_NORM_PIXEL",
+    "IMG_THIS_PIXEL",
+    "IMG_THIS_NORM_PIXEL",
+    "IMG_SIZE",
+];
+
+/// Whether the given name is one of the [**RESERVED_NAMES**](./constant.RESERVED_NAMES.html).
+///
+/// Names are compared case-sensitively, as they are by GLSL.
+pub fn is_reserved_name(name: &str) -> bool {
+    RESERVED_NAMES.contains(&name)
+}
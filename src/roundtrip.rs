@@ -0,0 +1,124 @@
+//! Diagnostics for serialization round-trips.
+
+use crate::Isf;
+use thiserror::Error;
+
+/// Describes where an `Isf` diverged after being serialized to JSON and parsed again.
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error("round-trip mismatch at `{field}`: {message}")]
+pub struct RoundtripMismatch {
+    /// The JSON pointer of the field that diverged, e.g. `/INPUTS/2/DEFAULT`. This is empty if
+    /// the JSON could not be produced or parsed at all.
+    pub field: String,
+    /// A description of the divergence.
+    pub message: String,
+}
+
+/// Check that the `Isf` serializes to JSON and parses back to an equal `Isf`.
+///
+/// If not, the returned mismatch names the first field found to diverge. This is useful for
+/// property testing code that produces or transforms `Isf`s.
+pub fn roundtrip_check(isf: &Isf) -> Result<(), RoundtripMismatch> {
+    let failed = |message: String| RoundtripMismatch {
+        field: String::new(),
+        message,
+    };
+    let json = serde_json::to_string(isf)
+        .map_err(|err| failed(format!("failed to serialize: {}", err)))?;
+    let parsed: Isf =
+        serde_json::from_str(&json).map_err(|err| failed(format!("failed to parse: {}", err)))?;
+    if *isf == parsed {
+        return Ok(());
+    }
+    // Prefer the precise location given by comparing the JSON of each.
+    let a = serde_json::to_value(isf).map_err(|err| failed(err.to_string()))?;
+    let b = serde_json::to_value(&parsed).map_err(|err| failed(err.to_string()))?;
+    if let Some(mismatch) = diff_values(&a, &b, String::new()) {
+        return Err(mismatch);
+    }
+    // Otherwise the difference is not visible within the JSON (e.g. a `NaN` written as `null`).
+    let field = diff_fields(isf, &parsed);
+    Err(RoundtripMismatch {
+        field,
+        message: "the value is not preserved by its JSON representation".to_string(),
+    })
+}
+
+/// Find the first difference between the two JSON values.
+fn diff_values(
+    a: &serde_json::Value,
+    b: &serde_json::Value,
+    pointer: String,
+) -> Option<RoundtripMismatch> {
+    use serde_json::Value;
+    let mismatch = |pointer: String, message: String| RoundtripMismatch {
+        field: pointer,
+        message,
+    };
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, va) in a {
+                let child = child_pointer(&pointer, key);
+                match b.get(key) {
+                    None => return Some(mismatch(child, "the field was lost".to_string())),
+                    Some(vb) => {
+                        if let Some(m) = diff_values(va, vb, child) {
+                            return Some(m);
+                        }
+                    }
+                }
+            }
+            let added = b.keys().find(|k| !a.contains_key(*k))?;
+            let child = child_pointer(&pointer, added);
+            Some(mismatch(child, "the field was added".to_string()))
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => a
+            .iter()
+            .zip(b)
+            .enumerate()
+            .find_map(|(ix, (va, vb))| {
+                diff_values(va, vb, child_pointer(&pointer, &ix.to_string()))
+            }),
+        _ if a == b => None,
+        _ => Some(mismatch(pointer, format!("{} became {}", a, b))),
+    }
+}
+
+/// Append the key to the JSON pointer, escaping `~` and `/` as per RFC 6901.
+fn child_pointer(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Find the first field of the `Isf`s that differs, as a JSON pointer.
+fn diff_fields(a: &Isf, b: &Isf) -> String {
+    let items = |name: &str, ix: Option<usize>| match ix {
+        Some(ix) => format!("/{}/{}", name, ix),
+        None => format!("/{}", name),
+    };
+    let first_diff = |a_len: usize, b_len: usize, eq: &dyn Fn(usize) -> bool| {
+        (0..a_len.min(b_len)).find(|&ix| !eq(ix))
+    };
+    if a.isfvsn != b.isfvsn {
+        "/ISFVSN".to_string()
+    } else if a.vsn != b.vsn {
+        "/VSN".to_string()
+    } else if a.description != b.description {
+        "/DESCRIPTION".to_string()
+    } else if a.label != b.label {
+        "/LABEL".to_string()
+    } else if a.credit != b.credit {
+        "/CREDIT".to_string()
+    } else if a.categories != b.categories {
+        "/CATEGORIES".to_string()
+    } else if a.inputs != b.inputs {
+        let eq = |ix: usize| a.inputs[ix] == b.inputs[ix];
+        items("INPUTS", first_diff(a.inputs.len(), b.inputs.len(), &eq))
+    } else if a.passes != b.passes {
+        let eq = |ix: usize| a.passes[ix] == b.passes[ix];
+        items("PASSES", first_diff(a.passes.len(), b.passes.len(), &eq))
+    } else if a.imported != b.imported {
+        "/IMPORTED".to_string()
+    } else {
+        "/PERSISTENT_BUFFERS".to_string()
+    }
+}
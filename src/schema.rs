@@ -0,0 +1,197 @@
+//! A JSON Schema describing the ISF top-level dict.
+
+use crate::InputType;
+use serde_json::{json, Value};
+
+/// Produce a JSON Schema (draft 07) describing the top-level dict of an ISF shader.
+///
+/// The schema is intended for editors validating or autocompleting ISF JSON. It describes the
+/// `TYPE` of each input along with the shape of its `DEFAULT`, `MIN`, `MAX` and `IDENTITY` for
+/// that type. The same leniency as the parser is permitted, e.g. a `TYPE` in any casing or numbers
+/// or `"YES"` where booleans are expected, as are the `null` values written by the serializer.
+/// Keys not described by the spec are allowed, as they are preserved by parsing.
+pub fn json_schema() -> Value {
+    let string = json!({ "type": ["string", "null"] });
+    let boolean = json!({ "type": ["boolean", "number", "string", "null"] });
+    let expr = json!({ "type": ["string", "number", "null"] });
+    let type_names: Vec<_> = InputType::ALL_TYPE_NAMES.iter().map(|ty| type_schema(ty)).collect();
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "ISF",
+        "description": "The top-level dict of an Interactive Shader Format shader.",
+        "type": "object",
+        "properties": {
+            "ISFVSN": string,
+            "VSN": string,
+            "DESCRIPTION": string,
+            "LABEL": string,
+            "CREDIT": string,
+            "CATEGORIES": { "type": "array", "items": { "type": "string" } },
+            "INPUTS": { "type": "array", "items": { "$ref": "#/definitions/input" } },
+            "PASSES": { "type": "array", "items": { "$ref": "#/definitions/pass" } },
+            "IMPORTED": {
+                "anyOf": [
+                    {
+                        "type": "object",
+                        "additionalProperties": { "$ref": "#/definitions/import" },
+                    },
+                    {
+                        "type": "array",
+                        "items": {
+                            "allOf": [
+                                { "$ref": "#/definitions/import" },
+                                {
+                                    "required": ["NAME"],
+                                    "properties": { "NAME": { "type": "string" } },
+                                },
+                            ],
+                        },
+                    },
+                ],
+            },
+            "PERSISTENT_BUFFERS": {
+                "anyOf": [
+                    { "type": "array", "items": { "type": "string" } },
+                    {
+                        "type": "object",
+                        "additionalProperties": {
+                            "type": "object",
+                            "properties": {
+                                "FLOAT": boolean,
+                                "WIDTH": expr,
+                                "HEIGHT": expr,
+                            },
+                        },
+                    },
+                ],
+            },
+        },
+        "definitions": {
+            "input": {
+                "type": "object",
+                "required": ["NAME", "TYPE"],
+                "properties": {
+                    "NAME": { "type": "string" },
+                    "LABEL": string,
+                    "HELP": string,
+                    "TYPE": { "anyOf": type_names },
+                },
+                "allOf": [
+                    input_values("bool", boolean.clone()),
+                    long_values(),
+                    input_values("float", json!({ "type": ["number", "null"] })),
+                    point2d_values(),
+                    input_values("color", json!({ "$ref": "#/definitions/color" })),
+                    audio_values("audio"),
+                    audio_values("audioFFT"),
+                ],
+            },
+            "color": {
+                "anyOf": [
+                    {
+                        "type": "array",
+                        "items": { "type": "number" },
+                        "minItems": 3,
+                        "maxItems": 4,
+                    },
+                    { "type": "string", "pattern": "^#([0-9a-fA-F]{6}|[0-9a-fA-F]{8})$" },
+                    { "type": "null" },
+                ],
+            },
+            "pass": {
+                "type": "object",
+                "properties": {
+                    "TARGET": string,
+                    "PERSISTENT": boolean,
+                    "FLOAT": boolean,
+                    "WIDTH": expr,
+                    "HEIGHT": expr,
+                },
+            },
+            "import": {
+                "type": "object",
+                "required": ["PATH"],
+                "properties": { "PATH": { "type": "string" } },
+            },
+        },
+    })
+}
+
+/// Constrain the `DEFAULT`, `MIN`, `MAX` and `IDENTITY` of inputs of the given `TYPE`.
+fn input_values(ty: &str, value: Value) -> Value {
+    conditional(
+        ty,
+        json!({
+            "DEFAULT": value,
+            "MIN": value,
+            "MAX": value,
+            "IDENTITY": value,
+        }),
+    )
+}
+
+fn long_values() -> Value {
+    let value = json!({ "type": ["integer", "null"] });
+    conditional(
+        "long",
+        json!({
+            "DEFAULT": value,
+            "MIN": value,
+            "MAX": value,
+            "IDENTITY": value,
+            "VALUES": { "type": "array", "items": { "type": "integer" } },
+            "LABELS": { "type": "array", "items": { "type": "string" } },
+        }),
+    )
+}
+
+fn point2d_values() -> Value {
+    let value = json!({
+        "anyOf": [
+            { "type": "array", "items": { "type": "number" }, "minItems": 2, "maxItems": 2 },
+            { "type": "null" },
+        ],
+    });
+    conditional(
+        "point2D",
+        json!({
+            "DEFAULT": value,
+            "MIN": value,
+            "MAX": value,
+            "IDENTITY": value,
+            "COORDINATE_SPACE": { "enum": ["normalized", "pixel"] },
+        }),
+    )
+}
+
+/// The `MAX` of an audio input is the number of samples or FFT columns.
+fn audio_values(ty: &str) -> Value {
+    let count = json!({ "type": ["integer", "null"], "minimum": 0 });
+    conditional(ty, json!({ "MAX": count }))
+}
+
+/// Apply the given property schemas to inputs of the given `TYPE`.
+fn conditional(ty: &str, properties: Value) -> Value {
+    json!({
+        "if": { "properties": { "TYPE": type_schema(ty) } },
+        "then": { "properties": properties },
+    })
+}
+
+/// Match the given `TYPE` case-insensitively, as the parser does.
+///
+/// JSON Schema patterns have no case-insensitive flag, so each letter is matched by a class of
+/// both cases, e.g. `^[fF][lL][oO][aA][tT]$`.
+fn type_schema(ty: &str) -> Value {
+    let mut pattern = "^".to_string();
+    for ch in ty.chars() {
+        if ch.is_ascii_alphabetic() {
+            let (lower, upper) = (ch.to_ascii_lowercase(), ch.to_ascii_uppercase());
+            pattern.push_str(&format!("[{}{}]", lower, upper));
+        } else {
+            pattern.push(ch);
+        }
+    }
+    pattern.push('$');
+    json!({ "type": "string", "pattern": pattern })
+}
@@ -0,0 +1,93 @@
+//! Serialization of an `Isf` to JSON with configurable output.
+
+use crate::Isf;
+use serde::{Serialize, Serializer};
+
+/// Options for serializing an `Isf` to a JSON string.
+///
+/// Keys that are not described by the spec, i.e. those within `Isf::extra` and `Input::extra`, are
+/// always emitted as-is regardless of the options.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SerializeOptions {
+    /// Round the `DEFAULT`, `MIN`, `MAX` and `IDENTITY` of every input (e.g. within `float`,
+    /// `point2D` and `color` inputs) to the given number of decimal places.
+    ///
+    /// By default values are emitted losslessly.
+    pub float_precision: Option<usize>,
+    /// Whether or not to pretty-print the JSON.
+    pub pretty: bool,
+    /// Whether or not to write optional spec keys without a value as an explicit `null` (or an
+    /// empty array or dict, or `false`), e.g. `"MIN": null` or `"PERSISTENT": false`.
+    ///
+    /// By default such fields are omitted, producing minimal output. Some hosts require the keys
+    /// to be present regardless.
+    pub emit_null_fields: bool,
+}
+
+/// Serialization of a dict that may also write the optional spec keys without a value.
+///
+/// The `Serialize` impls of the implementing types omit such keys. Those described by
+/// [**emit_null_fields**](./struct.SerializeOptions.html#structfield.emit_null_fields) are written
+/// when it is set.
+pub(crate) trait SerializeDict {
+    fn serialize_dict<S>(&self, s: S, emit_null_fields: bool) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+}
+
+/// Serializes the wrapped dict, writing optional spec keys without a value if the flag is set.
+pub(crate) struct Dict<'a, T>(pub &'a T, pub bool);
+
+impl<T> Serialize for Dict<'_, T>
+where
+    T: SerializeDict,
+{
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize_dict(s, self.1)
+    }
+}
+
+/// Serialize the `Isf` to a JSON string using the given options.
+pub fn to_string_with_options(
+    isf: &Isf,
+    options: &SerializeOptions,
+) -> Result<String, serde_json::Error> {
+    let mut value = serde_json::to_value(Dict(isf, options.emit_null_fields))?;
+    if let Some(precision) = options.float_precision {
+        let inputs = value.get_mut("INPUTS").and_then(|i| i.as_array_mut());
+        for input in inputs.into_iter().flatten() {
+            for key in &["DEFAULT", "MIN", "MAX", "IDENTITY"] {
+                if let Some(v) = input.get_mut(*key) {
+                    round_floats(v, precision);
+                }
+            }
+        }
+    }
+    if options.pretty {
+        serde_json::to_string_pretty(&value)
+    } else {
+        serde_json::to_string(&value)
+    }
+}
+
+/// Round all floating point numbers within the JSON value to the given number of decimal places.
+///
+/// Integers (e.g. the values of `long` inputs) are left untouched.
+fn round_floats(value: &mut serde_json::Value, precision: usize) {
+    match value {
+        serde_json::Value::Number(n) if n.is_f64() => {
+            let f = n.as_f64().unwrap();
+            // Round via the decimal representation to avoid introducing binary noise.
+            let rounded: f64 = format!("{:.*}", precision, f).parse().unwrap_or(f);
+            if let Some(rounded) = serde_json::Number::from_f64(rounded) {
+                *n = rounded;
+            }
+        }
+        serde_json::Value::Array(arr) => arr.iter_mut().for_each(|v| round_floats(v, precision)),
+        serde_json::Value::Object(obj) => obj.values_mut().for_each(|v| round_floats(v, precision)),
+        _ => (),
+    }
+}
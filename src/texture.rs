@@ -0,0 +1,66 @@
+//! Enumeration of the textures that a host must provide to an ISF shader.
+
+use crate::{InputType, Isf};
+
+/// The source of a texture required by an ISF shader.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TextureKind {
+    /// An `image` input.
+    Image,
+    /// An `audio` input, whose samples are provided as a texture.
+    Audio,
+    /// An `audioFFT` input, whose FFT columns are provided as a texture.
+    AudioFft,
+    /// An image within the `IMPORTED` dict.
+    Imported,
+    /// The `TARGET` buffer of a pass.
+    Target,
+}
+
+/// A texture that a host must bind in order to render an ISF shader.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextureRequirement {
+    pub kind: TextureKind,
+    /// The name by which the shader refers to the texture, i.e. the name of the input, the key
+    /// within `IMPORTED` or the pass `TARGET`.
+    pub name: String,
+}
+
+impl Isf {
+    /// Enumerate every texture that a host must provide to render the shader.
+    ///
+    /// The textures are listed in the following order:
+    ///
+    /// 1. `image`, `audio` and `audioFFT` inputs in the order in which they are declared.
+    /// 2. `IMPORTED` images in order of name.
+    /// 3. Pass `TARGET`s in the order in which they are first rendered to. A target rendered to
+    ///    by multiple passes is listed once.
+    pub fn required_textures(&self) -> Vec<TextureRequirement> {
+        let mut textures = vec![];
+        for input in &self.inputs {
+            let kind = match input.ty {
+                InputType::Image => TextureKind::Image,
+                InputType::Audio(_) => TextureKind::Audio,
+                InputType::AudioFft(_) => TextureKind::AudioFft,
+                _ => continue,
+            };
+            let name = input.name.clone();
+            textures.push(TextureRequirement { kind, name });
+        }
+        for name in self.imported.keys() {
+            let kind = TextureKind::Imported;
+            let name = name.clone();
+            textures.push(TextureRequirement { kind, name });
+        }
+        for target in self.passes.iter().filter_map(|p| p.target.as_ref()) {
+            let texture = TextureRequirement {
+                kind: TextureKind::Target,
+                name: target.clone(),
+            };
+            if !textures.contains(&texture) {
+                textures.push(texture);
+            }
+        }
+        textures
+    }
+}
@@ -0,0 +1,255 @@
+//! Layout of ISF input values within a uniform buffer.
+
+use crate::{Input, InputType, InputValue, Isf};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// The layout of an ISF shader's input values within a `std140` uniform block.
+///
+/// Only inputs that are represented by a uniform value are included. Image and audio inputs are
+/// bound as textures and do not appear within the layout.
+///
+/// All values are encoded as little-endian. `bool` values occupy 4 bytes and are encoded as `0`
+/// or `1` as per `std140`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniformLayout {
+    /// The uniforms in order of their offset within the block.
+    pub uniforms: Vec<Uniform>,
+    /// The total size of the block in bytes, rounded up to a multiple of 16.
+    pub size: usize,
+}
+
+/// A single uniform within a `UniformLayout`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Uniform {
+    /// The name of the input that the uniform represents.
+    pub name: String,
+    pub ty: UniformType,
+    /// The byte offset of the uniform from the start of the block.
+    pub offset: usize,
+    /// Whether or not the uniform represents an `event` input.
+    pub is_event: bool,
+}
+
+/// Returned by [**UniformLayout::pack**](./struct.UniformLayout.html#method.pack) when a uniform
+/// does not lie within the `size` of the layout.
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error("the uniform `{name}` at byte {offset} does not fit within the {size} byte block")]
+pub struct UniformOutOfBounds {
+    /// The name of the uniform.
+    pub name: String,
+    /// The byte offset of the uniform.
+    pub offset: usize,
+    /// The size of the layout's block in bytes.
+    pub size: usize,
+}
+
+/// The GLSL types used to represent ISF input values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UniformType {
+    Bool,
+    Int,
+    Float,
+    Vec2,
+    Vec3,
+    Vec4,
+}
+
+impl UniformType {
+    /// The GLSL name for the type.
+    pub fn glsl_str(&self) -> &'static str {
+        match *self {
+            UniformType::Bool => "bool",
+            UniformType::Int => "int",
+            UniformType::Float => "float",
+            UniformType::Vec2 => "vec2",
+            UniformType::Vec3 => "vec3",
+            UniformType::Vec4 => "vec4",
+        }
+    }
+
+    /// The size of the type in bytes within a `std140` block.
+    pub fn size(&self) -> usize {
+        match *self {
+            UniformType::Bool | UniformType::Int | UniformType::Float => 4,
+            UniformType::Vec2 => 8,
+            UniformType::Vec3 => 12,
+            UniformType::Vec4 => 16,
+        }
+    }
+
+    /// The base alignment of the type in bytes within a `std140` block.
+    pub fn align(&self) -> usize {
+        match *self {
+            UniformType::Bool | UniformType::Int | UniformType::Float => 4,
+            UniformType::Vec2 => 8,
+            UniformType::Vec3 | UniformType::Vec4 => 16,
+        }
+    }
+}
+
+impl InputType {
+    /// The uniform type used to represent the input's value.
+    ///
+    /// `color` inputs are represented by a `vec3` if they are RGB colors and a `vec4` otherwise
+    /// (see [**InputColor::channels**](./struct.InputColor.html#method.channels)). Matching the
+    /// arity of the values ensures that swizzles within the shader body (e.g. `tint.a`) behave as
+    /// the author intended. When the arity is ambiguous (e.g. no values are specified), `vec4` is
+    /// used as per the ISF spec.
+    ///
+    /// Returns `None` for inputs that are bound as textures (`image`, `audio` and `audioFFT`).
+    pub fn uniform_type(&self) -> Option<UniformType> {
+        let ty = match *self {
+            InputType::Event | InputType::Bool(_) => UniformType::Bool,
+            InputType::Long(_) => UniformType::Int,
+            InputType::Float(_) => UniformType::Float,
+            InputType::Point2d(_) => UniformType::Vec2,
+            InputType::Color(ref c) if c.channels() == Some(3) => UniformType::Vec3,
+            InputType::Color(_) => UniformType::Vec4,
+            InputType::Image | InputType::Audio(_) | InputType::AudioFft(_) => return None,
+        };
+        Some(ty)
+    }
+}
+
+impl Input {
+    /// Produce the GLSL uniform declaration for the input's value, e.g. `uniform vec2 center;`.
+    ///
+    /// Returns `None` for inputs that are bound as textures. See
+    /// [**InputType::uniform_type**](./enum.InputType.html#method.uniform_type) for details on
+    /// how the type is chosen.
+    pub fn uniform_declaration(&self) -> Option<String> {
+        let ty = self.ty.uniform_type()?;
+        Some(format!("uniform {} {};", ty.glsl_str(), self.name))
+    }
+}
+
+impl Isf {
+    /// Produce the GLSL uniform declarations for all of the shader's inputs, one per line in the
+    /// order in which the inputs are declared.
+    ///
+    /// Inputs with a value are declared as per
+    /// [**Input::uniform_declaration**](./struct.Input.html#method.uniform_declaration). `image`,
+    /// `audio` and `audioFFT` inputs are declared as a `sampler2D`, e.g.
+    /// `uniform sampler2D inputImage;`.
+    pub fn uniform_declarations(&self) -> String {
+        let mut decls = String::new();
+        for input in &self.inputs {
+            let decl = input
+                .uniform_declaration()
+                .unwrap_or_else(|| format!("uniform sampler2D {};", input.name));
+            decls.push_str(&decl);
+            decls.push('\n');
+        }
+        decls
+    }
+
+    /// Produce the `std140` layout of the shader's input values in the order in which the inputs
+    /// are declared.
+    pub fn uniform_layout(&self) -> UniformLayout {
+        let mut uniforms = vec![];
+        let mut offset = 0;
+        for input in &self.inputs {
+            let ty = match input.ty.uniform_type() {
+                None => continue,
+                Some(ty) => ty,
+            };
+            offset = align_to(offset, ty.align());
+            uniforms.push(Uniform {
+                name: input.name.clone(),
+                ty,
+                offset,
+                is_event: matches!(input.ty, InputType::Event),
+            });
+            offset += ty.size();
+        }
+        let size = align_to(offset, 16);
+        UniformLayout { uniforms, size }
+    }
+}
+
+impl UniformLayout {
+    /// Pack the given values into a buffer matching the layout.
+    ///
+    /// Uniforms without a value in the map, or whose value does not match the uniform's type, are
+    /// left zeroed.
+    ///
+    /// Returns an error if a uniform lies beyond the `size` of the layout, which is only possible
+    /// for a layout that has been modified since being produced by
+    /// [**Isf::uniform_layout**](./struct.Isf.html#method.uniform_layout).
+    pub fn pack(
+        &self,
+        values: &BTreeMap<String, InputValue>,
+    ) -> Result<Vec<u8>, UniformOutOfBounds> {
+        let mut bytes = vec![0u8; self.size];
+        for uniform in &self.uniforms {
+            let value = match values.get(&uniform.name) {
+                None => continue,
+                Some(v) => v,
+            };
+            let mut words = [0u32; 4];
+            match (uniform.ty, *value) {
+                (UniformType::Bool, InputValue::Bool(b))
+                | (UniformType::Bool, InputValue::Event(b)) => words[0] = b as u32,
+                (UniformType::Int, InputValue::Long(i)) => words[0] = i as u32,
+                (UniformType::Float, InputValue::Float(f)) => words[0] = f.to_bits(),
+                (UniformType::Vec2, InputValue::Point2d(p)) => {
+                    words[0] = p[0].to_bits();
+                    words[1] = p[1].to_bits();
+                }
+                (UniformType::Vec3, InputValue::Color(c))
+                | (UniformType::Vec4, InputValue::Color(c)) => {
+                    for (w, f) in words.iter_mut().zip(c.iter()) {
+                        *w = f.to_bits();
+                    }
+                }
+                _ => continue,
+            }
+            let range = uniform.offset..uniform.offset.saturating_add(uniform.ty.size());
+            let dst = bytes.get_mut(range).ok_or_else(|| UniformOutOfBounds {
+                name: uniform.name.clone(),
+                offset: uniform.offset,
+                size: self.size,
+            })?;
+            for (chunk, w) in dst.chunks_mut(4).zip(words.iter()) {
+                chunk.copy_from_slice(&w.to_le_bytes());
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Read the values of each uniform from the given buffer.
+    ///
+    /// This is the inverse of [**pack**](#method.pack). Uniforms that lie beyond the end of the
+    /// buffer are omitted from the result. `vec3` colors are read with an alpha of `1.0`.
+    pub fn read_values(&self, bytes: &[u8]) -> BTreeMap<String, InputValue> {
+        let mut values = BTreeMap::new();
+        for uniform in &self.uniforms {
+            let src = match bytes.get(uniform.offset..uniform.offset + uniform.ty.size()) {
+                None => continue,
+                Some(src) => src,
+            };
+            let mut words = [0u32; 4];
+            for (w, chunk) in words.iter_mut().zip(src.chunks(4)) {
+                *w = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            }
+            let f = |ix: usize| f32::from_bits(words[ix]);
+            let value = match uniform.ty {
+                UniformType::Bool if uniform.is_event => InputValue::Event(words[0] != 0),
+                UniformType::Bool => InputValue::Bool(words[0] != 0),
+                UniformType::Int => InputValue::Long(words[0] as i32),
+                UniformType::Float => InputValue::Float(f(0)),
+                UniformType::Vec2 => InputValue::Point2d([f(0), f(1)]),
+                UniformType::Vec3 => InputValue::Color([f(0), f(1), f(2), 1.0]),
+                UniformType::Vec4 => InputValue::Color([f(0), f(1), f(2), f(3)]),
+            };
+            values.insert(uniform.name.clone(), value);
+        }
+        values
+    }
+}
+
+/// Round `offset` up to the next multiple of `align`.
+fn align_to(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
@@ -0,0 +1,264 @@
+//! Validation of the consistency of ISF shaders.
+
+use crate::{glsl, is_reserved_name, ExprError, Input, InputType, Isf, Pass};
+use thiserror::Error;
+
+/// An inconsistency within an ISF shader, or between its metadata and its body.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum ValidationError {
+    #[error("the `DEFAULT` {default} of input `{input}` lies outside [{min}, {max}]")]
+    DefaultOutOfRange {
+        input: String,
+        default: f64,
+        min: f64,
+        max: f64,
+    },
+    #[error("the `MIN` {min} of input `{input}` is greater than its `MAX` {max}")]
+    MinMaxSwapped { input: String, min: f64, max: f64 },
+    #[error("the `MIN` {min} of input `{input}` is greater than its `MAX` {max} along `{axis}`")]
+    PointMinMaxSwapped {
+        input: String,
+        /// The inverted axis, either `'x'` or `'y'`.
+        axis: char,
+        min: f32,
+        max: f32,
+    },
+    #[error("the `DEFAULT` {default} of `long` input `{input}` is not one of its `VALUES`")]
+    DefaultNotInValues { input: String, default: i32 },
+    #[error("the input name `{input}` is not a valid GLSL identifier")]
+    InvalidInputName { input: String },
+    #[error("the input name `{input}` is reserved by ISF")]
+    ReservedInputName { input: String },
+    #[error("multiple inputs are named `{input}`")]
+    DuplicateInput { input: String },
+    #[error("the `long` input `{input}` has {values} `VALUES` but {labels} `LABELS`")]
+    LongLabelCountMismatch {
+        input: String,
+        values: usize,
+        labels: usize,
+    },
+    #[error("the input `{input}` is never referenced by the shader body")]
+    UnusedInput { input: String },
+    #[error("the uniform `{name}` is declared by the shader body but is not an ISF input")]
+    UndeclaredUniform { name: String },
+    #[error("the shader declares {passes} passes but never reads `PASSINDEX`")]
+    PassIndexUnused { passes: usize },
+    #[error("the imported image `{name}` is never referenced by the shader body")]
+    UnusedImport { name: String },
+    #[error("the dependencies between passes {passes:?} form a cycle")]
+    PassCycle { passes: Vec<usize> },
+    #[error("pass {pass} is `PERSISTENT` but has no `TARGET` to persist")]
+    PersistentPassWithoutTarget { pass: usize },
+    #[error("pass {pass} is not the last pass but has no `TARGET`")]
+    IntermediatePassWithoutTarget { pass: usize },
+    #[error("the `{field}` of pass {pass} is not a valid expression: {err}")]
+    InvalidDimension {
+        pass: usize,
+        field: &'static str,
+        err: ExprError,
+    },
+}
+
+/// The uniforms declared by ISF hosts in addition to those of the shader's inputs.
+const RESERVED_UNIFORMS: &[&str] = &[
+    "PASSINDEX",
+    "RENDERSIZE",
+    "TIME",
+    "TIMEDELTA",
+    "DATE",
+    "FRAMEINDEX",
+];
+
+impl Input {
+    /// Check that the input's `MIN` is no greater than its `MAX`.
+    ///
+    /// A [**ValidationError::MinMaxSwapped**](./enum.ValidationError.html) is produced for an
+    /// inverted `float` or `long` input. The components of a `point2D` input are checked
+    /// independently, producing a **ValidationError::PointMinMaxSwapped** per inverted axis.
+    /// Inputs missing either bound are never reported.
+    pub fn inverted_bounds(&self) -> Vec<ValidationError> {
+        if let InputType::Point2d(ref p) = self.ty {
+            let (min, max) = match (p.min, p.max) {
+                (Some(min), Some(max)) => (min, max),
+                _ => return vec![],
+            };
+            return ['x', 'y']
+                .iter()
+                .zip(min.to_array().iter().zip(&max.to_array()))
+                .filter(|(_, (min, max))| min > max)
+                .map(
+                    |(&axis, (&min, &max))| ValidationError::PointMinMaxSwapped {
+                        input: self.name.clone(),
+                        axis,
+                        min,
+                        max,
+                    },
+                )
+                .collect();
+        }
+        match self.numeric_bounds() {
+            Some((min, max)) if min > max => vec![ValidationError::MinMaxSwapped {
+                input: self.name.clone(),
+                min,
+                max,
+            }],
+            _ => vec![],
+        }
+    }
+}
+
+impl Pass {
+    /// Check the invariants of the pass at the given index within `PASSES`.
+    ///
+    /// A persistent pass must have a `TARGET`, as must every pass besides the last, and the
+    /// `WIDTH` and `HEIGHT` must be valid expressions.
+    pub(crate) fn errors(&self, ix: usize, is_last: bool) -> Vec<ValidationError> {
+        let mut errors = vec![];
+        if self.target.is_none() {
+            if self.persistent {
+                errors.push(ValidationError::PersistentPassWithoutTarget { pass: ix });
+            } else if !is_last {
+                errors.push(ValidationError::IntermediatePassWithoutTarget { pass: ix });
+            }
+        }
+        let dims = [("WIDTH", self.width_expr()), ("HEIGHT", self.height_expr())];
+        for (field, expr) in dims {
+            if let Err(err) = expr {
+                errors.push(ValidationError::InvalidDimension {
+                    pass: ix,
+                    field,
+                    err,
+                });
+            }
+        }
+        errors
+    }
+}
+
+impl Isf {
+    /// Check the shader's metadata for internal inconsistencies.
+    ///
+    /// All problems are collected rather than stopping at the first. The following are checked:
+    ///
+    /// - each input's `NAME` is a valid GLSL identifier (see
+    ///   [**glsl::is_identifier**](./glsl/fn.is_identifier.html)) and is not reserved by ISF (see
+    ///   [**RESERVED_NAMES**](./constant.RESERVED_NAMES.html)), besides an `image` input named
+    ///   `inputImage`,
+    /// - no two inputs share the same `NAME`,
+    /// - the `MIN` of each `float`, `long` and `point2D` input is no greater than its `MAX` (see
+    ///   [**Input::inverted_bounds**](./struct.Input.html#method.inverted_bounds)),
+    /// - the `DEFAULT` of each `float` and `long` input lies within its `MIN` and `MAX`,
+    /// - the `DEFAULT` of each `long` input with `VALUES` is one of the values,
+    /// - the `VALUES` and `LABELS` of each `long` input have the same length,
+    /// - each `PERSISTENT` pass and each pass but the last has a `TARGET` and
+    /// - the `WIDTH` and `HEIGHT` of each pass are valid expressions.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        for (ix, input) in self.inputs.iter().enumerate() {
+            let name = || input.name.clone();
+            // Report each duplicated name once, upon its second occurrence.
+            let earlier = self.inputs[..ix].iter().filter(|i| i.name == input.name);
+            if earlier.count() == 1 {
+                errors.push(ValidationError::DuplicateInput { input: name() });
+            }
+            if !glsl::is_identifier(&input.name) {
+                errors.push(ValidationError::InvalidInputName { input: name() });
+            } else if is_reserved_name(&input.name)
+                && !(input.name == "inputImage" && input.ty == InputType::Image)
+            {
+                errors.push(ValidationError::ReservedInputName { input: name() });
+            }
+            let inverted = input.inverted_bounds();
+            let bounds_ok = inverted.is_empty();
+            errors.extend(inverted);
+            if bounds_ok {
+                if let Some((min, max)) = input.numeric_bounds() {
+                    if let Some(default) = input.numeric_default() {
+                        if default < min || default > max {
+                            errors.push(ValidationError::DefaultOutOfRange {
+                                input: name(),
+                                default,
+                                min,
+                                max,
+                            });
+                        }
+                    }
+                }
+            }
+            if let InputType::Long(ref l) = input.ty {
+                if let Some(default) = l.default {
+                    if !l.values.is_empty() && !l.values.contains(&default) {
+                        errors.push(ValidationError::DefaultNotInValues {
+                            input: name(),
+                            default,
+                        });
+                    }
+                }
+                if !l.labels.is_empty() && l.labels.len() != l.values.len() {
+                    errors.push(ValidationError::LongLabelCountMismatch {
+                        input: name(),
+                        values: l.values.len(),
+                        labels: l.labels.len(),
+                    });
+                }
+            }
+        }
+        let last = self.passes.len().saturating_sub(1);
+        for (ix, pass) in self.passes.iter().enumerate() {
+            errors.extend(pass.errors(ix, ix == last));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Check the shader's metadata along with its body, e.g. as a linting or CI tool would.
+    ///
+    /// In addition to the checks performed by [**validate**](#method.validate), this checks
+    /// that:
+    ///
+    /// - every input and imported image is referenced by the body,
+    /// - every `uniform` declared by the body is either an input or provided by ISF hosts (e.g.
+    ///   `TIME`), as no other uniforms are ever set,
+    /// - a multi-pass shader reads `PASSINDEX` (see
+    ///   [**check_passindex_usage**](#method.check_passindex_usage)) and
+    /// - the passes can be ordered (see [**execution_order**](#method.execution_order)).
+    pub fn validate_with_body(&self, body: &str) -> Result<(), Vec<ValidationError>> {
+        let mut errors = self.validate().err().unwrap_or_default();
+        let idents = glsl::identifiers(body);
+        for input in &self.inputs {
+            if !idents.contains(&input.name) {
+                errors.push(ValidationError::UnusedInput {
+                    input: input.name.clone(),
+                });
+            }
+        }
+        for uniform in glsl::declared_uniforms(body) {
+            if self.input(&uniform.name).is_none()
+                && !RESERVED_UNIFORMS.contains(&&uniform.name[..])
+            {
+                errors.push(ValidationError::UndeclaredUniform { name: uniform.name });
+            }
+        }
+        if self.check_passindex_usage(body).is_some() {
+            errors.push(ValidationError::PassIndexUnused {
+                passes: self.passes.len(),
+            });
+        }
+        for name in self.imported.keys() {
+            if !idents.contains(name) {
+                errors.push(ValidationError::UnusedImport { name: name.clone() });
+            }
+        }
+        if let Err(err) = self.execution_order(body) {
+            errors.push(ValidationError::PassCycle { passes: err.passes });
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
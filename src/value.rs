@@ -0,0 +1,135 @@
+//! Runtime values for ISF inputs.
+
+use crate::{Input, InputType, InputTypeKind, Isf};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// The value of an ISF input at runtime, e.g. as uploaded to a shader's uniforms.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputValue {
+    /// Whether or not the event was triggered.
+    Event(bool),
+    Bool(bool),
+    Long(i32),
+    Float(f32),
+    Point2d([f32; 2]),
+    /// An RGBA color.
+    Color([f32; 4]),
+    /// Images are bound as textures rather than uniform values.
+    Image,
+    /// Audio is bound as a texture rather than a uniform value.
+    Audio,
+    /// Audio FFT data is bound as a texture rather than a uniform value.
+    AudioFft,
+}
+
+/// Returned by [**Input::clamp_value**](./struct.Input.html#method.clamp_value) when the value
+/// does not match the type of the input.
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error("the value {value:?} does not match the type of input `{input}`")]
+pub struct TypeMismatch {
+    pub input: String,
+    pub value: InputValue,
+}
+
+impl InputValue {
+    /// The kind of input to which the value belongs.
+    pub fn kind(&self) -> InputTypeKind {
+        match *self {
+            InputValue::Event(_) => InputTypeKind::Event,
+            InputValue::Bool(_) => InputTypeKind::Bool,
+            InputValue::Long(_) => InputTypeKind::Long,
+            InputValue::Float(_) => InputTypeKind::Float,
+            InputValue::Point2d(_) => InputTypeKind::Point2d,
+            InputValue::Color(_) => InputTypeKind::Color,
+            InputValue::Image => InputTypeKind::Image,
+            InputValue::Audio => InputTypeKind::Audio,
+            InputValue::AudioFft => InputTypeKind::AudioFft,
+        }
+    }
+}
+
+impl Input {
+    /// Clamp the value to the input's bounds, e.g. prior to uploading it to a shader.
+    ///
+    /// `float` and `long` values are clamped to the `MIN` and `MAX`, and `point2D` values are
+    /// clamped to them per component. Values of `long` menus are instead replaced by the nearest
+    /// of the `VALUES`, favouring the earlier value in the case of a tie. Bounds that are missing
+    /// are not enforced and values of all other types are returned unchanged.
+    ///
+    /// Returns an error if the value does not match the input's type.
+    pub fn clamp_value(&self, value: InputValue) -> Result<InputValue, TypeMismatch> {
+        if value.kind() != self.ty.kind() {
+            let input = self.name.clone();
+            return Err(TypeMismatch { input, value });
+        }
+        fn clamp<T: PartialOrd>(mut v: T, min: Option<T>, max: Option<T>) -> T {
+            if let Some(min) = min.filter(|min| v < *min) {
+                v = min;
+            }
+            if let Some(max) = max.filter(|max| v > *max) {
+                v = max;
+            }
+            v
+        }
+        let value = match (&self.ty, value) {
+            (InputType::Float(f), InputValue::Float(v)) => {
+                InputValue::Float(clamp(v, f.min, f.max))
+            }
+            (InputType::Long(l), InputValue::Long(v)) if l.is_menu() => {
+                let nearest = l
+                    .values
+                    .iter()
+                    .min_by_key(|&&option| (i64::from(option) - i64::from(v)).abs());
+                InputValue::Long(nearest.copied().unwrap_or(v))
+            }
+            (InputType::Long(l), InputValue::Long(v)) => InputValue::Long(clamp(v, l.min, l.max)),
+            (InputType::Point2d(p), InputValue::Point2d([x, y])) => {
+                let x = clamp(x, p.min.map(|m| m.x), p.max.map(|m| m.x));
+                let y = clamp(y, p.min.map(|m| m.y), p.max.map(|m| m.y));
+                InputValue::Point2d([x, y])
+            }
+            (_, value) => value,
+        };
+        Ok(value)
+    }
+
+    /// The input's `DEFAULT` as an `InputValue`, falling back to a zero value of the appropriate
+    /// type if there is none.
+    ///
+    /// `long` menus without a `DEFAULT` fall back to their first value. `color` values are given
+    /// as RGBA (see [**InputColor::default_rgba**](./struct.InputColor.html#method.default_rgba)),
+    /// falling back to opaque black. `point2D` values are given in the input's own coordinate
+    /// space.
+    pub fn default_value(&self) -> InputValue {
+        match self.ty {
+            InputType::Event => InputValue::Event(false),
+            InputType::Bool(ref b) => InputValue::Bool(b.default_or_false()),
+            InputType::Long(ref l) => InputValue::Long(l.default_or_first().unwrap_or(0)),
+            InputType::Float(ref f) => InputValue::Float(f.default_or(0.0)),
+            InputType::Point2d(ref p) => InputValue::Point2d(p.default_or_zero().to_array()),
+            InputType::Color(ref c) => {
+                InputValue::Color(c.default_rgba().unwrap_or([0.0, 0.0, 0.0, 1.0]))
+            }
+            InputType::Image => InputValue::Image,
+            InputType::Audio(_) => InputValue::Audio,
+            InputType::AudioFft(_) => InputValue::AudioFft,
+        }
+    }
+}
+
+impl Isf {
+    /// The default value of each input by name, e.g. for the first frame rendered by a host.
+    ///
+    /// See [**Input::default_value**](./struct.Input.html#method.default_value). Where multiple
+    /// inputs share a name, the first takes precedence.
+    pub fn default_values(&self) -> BTreeMap<String, InputValue> {
+        let mut values = BTreeMap::new();
+        for input in &self.inputs {
+            values
+                .entry(input.name.clone())
+                .or_insert_with(|| input.default_value());
+        }
+        values
+    }
+}
@@ -0,0 +1,152 @@
+//! Handling of the differences between versions of the ISF specification.
+
+use crate::{InputType, Isf};
+use std::fmt;
+
+/// A version of the ISF specification, e.g. as declared by `ISFVSN`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IsfVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+/// A summary of the changes made by
+/// [**Isf::upgrade_to_v2**](./struct.Isf.html#method.upgrade_to_v2).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UpgradeReport {
+    /// Whether or not the shader was upgraded. This is `false` for shaders already declaring v2
+    /// or later.
+    pub upgraded: bool,
+    /// The `ISFVSN` prior to the upgrade.
+    pub previous_version: Option<String>,
+    /// The indices of passes that were marked `PERSISTENT` due to a v1 persistent buffer.
+    pub persistent_passes: Vec<usize>,
+    /// v1 persistent buffers that are not the `TARGET` of any pass.
+    ///
+    /// These are left within `Isf::persistent_buffers` so that no information is lost.
+    pub unmatched_persistent_buffers: Vec<String>,
+}
+
+impl IsfVersion {
+    /// Version 1.0 of the specification.
+    pub const V1: IsfVersion = IsfVersion { major: 1, minor: 0 };
+    /// Version 2.0 of the specification.
+    pub const V2: IsfVersion = IsfVersion { major: 2, minor: 0 };
+
+    /// Parse a version string such as `"2"` or `"2.0"`.
+    ///
+    /// A missing minor version is assumed to be `0`. Returns `None` if the string is not numeric.
+    pub fn parse(vsn: &str) -> Option<Self> {
+        let mut parts = vsn.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = match parts.next() {
+            None => 0,
+            Some(minor) => minor.parse().ok()?,
+        };
+        Some(IsfVersion { major, minor })
+    }
+}
+
+impl fmt::Display for IsfVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl UpgradeReport {
+    /// Whether or not the upgrade changed anything.
+    pub fn is_empty(&self) -> bool {
+        !self.upgraded
+    }
+}
+
+impl Isf {
+    /// The version of the specification declared by `ISFVSN`.
+    ///
+    /// Returns `None` if `ISFVSN` is missing or is not a numeric version. See
+    /// [**IsfVersion::parse**](./struct.IsfVersion.html#method.parse). Note that the spec treats
+    /// shaders without an `ISFVSN` as v1.
+    pub fn version(&self) -> Option<IsfVersion> {
+        self.isfvsn.as_deref().and_then(IsfVersion::parse)
+    }
+
+    /// Upgrade a v1 ISF to v2 in place.
+    ///
+    /// This sets `ISFVSN` to `"2.0"` and migrates the v1 `PERSISTENT_BUFFERS` to the `PERSISTENT`
+    /// flag of the pass rendering to each buffer, moving any `WIDTH`, `HEIGHT` and `FLOAT`
+    /// specified for the buffer onto the pass where the pass does not already specify them.
+    ///
+    /// Input `TYPE`s are always serialized using their canonical spelling, so no migration of
+    /// inputs is necessary.
+    ///
+    /// Shaders already declaring v2 or later are left unchanged.
+    pub fn upgrade_to_v2(&mut self) -> UpgradeReport {
+        let mut report = UpgradeReport::default();
+        let declared = self.version();
+        if declared.is_some_and(|v| v.major >= 2) {
+            return report;
+        }
+        report.upgraded = true;
+        report.previous_version = self.isfvsn.replace("2.0".to_string());
+        let buffers = std::mem::take(&mut self.persistent_buffers);
+        for (name, buffer) in buffers {
+            let mut matched = false;
+            for (ix, pass) in self.passes.iter_mut().enumerate() {
+                if pass.target.as_ref() != Some(&name) {
+                    continue;
+                }
+                matched = true;
+                pass.persistent = true;
+                pass.float |= buffer.float;
+                if pass.width.is_none() {
+                    pass.width = buffer.width.clone();
+                    pass.width_is_number = buffer.width_is_number;
+                }
+                if pass.height.is_none() {
+                    pass.height = buffer.height.clone();
+                    pass.height_is_number = buffer.height_is_number;
+                }
+                report.persistent_passes.push(ix);
+            }
+            if !matched {
+                report.unmatched_persistent_buffers.push(name.clone());
+                self.persistent_buffers.insert(name, buffer);
+            }
+        }
+        report.persistent_passes.sort_unstable();
+        report
+    }
+
+    /// The minimal version of the specification required by the features the shader uses.
+    ///
+    /// v2 is required by `audio` and `audioFFT` inputs and by `PERSISTENT` passes. All other
+    /// shaders only require v1.
+    pub fn detected_version(&self) -> IsfVersion {
+        let audio = self
+            .inputs
+            .iter()
+            .any(|i| matches!(i.ty, InputType::Audio(_) | InputType::AudioFft(_)));
+        let persistent = self.passes.iter().any(|p| p.persistent);
+        if audio || persistent {
+            IsfVersion::V2
+        } else {
+            IsfVersion::V1
+        }
+    }
+
+    /// Lower the declared `ISFVSN` to the [**detected_version**](#method.detected_version) if the
+    /// shader declares a higher version than it requires, e.g. after removing v2-only inputs.
+    ///
+    /// This never raises the version. A missing or non-numeric `ISFVSN` is left unchanged. See
+    /// [**upgrade_to_v2**](#method.upgrade_to_v2) for raising the version.
+    pub fn minimize_version(&mut self) {
+        let declared = match self.version() {
+            None => return,
+            Some(v) => v,
+        };
+        let detected = self.detected_version();
+        if detected < declared {
+            self.isfvsn = Some(detected.to_string());
+        }
+    }
+}
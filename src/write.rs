@@ -0,0 +1,30 @@
+//! Writing an `Isf` back into GLSL source.
+
+use crate::{find_comment, Isf, ParseError};
+
+/// Serialize the `Isf` and substitute it for the ISF blob within the given GLSL source.
+///
+/// The contents of the top comment (see [**find_comment**](./fn.find_comment.html)) are replaced
+/// with pretty-printed JSON. Whitespace surrounding the JSON within the comment and all GLSL
+/// outside of the comment are preserved verbatim. If the source contains no ISF blob, a new
+/// comment is inserted at the top.
+///
+/// Any `*/` sequence within a JSON string is written as `*\/` so that the comment is not closed
+/// early by GLSL compilers.
+pub fn write_comment(glsl_src: &str, isf: &Isf) -> Result<String, ParseError> {
+    let json = serde_json::to_string_pretty(isf).map_err(|err| ParseError::Serialize { err })?;
+    // `*` can only appear within JSON strings, where `/` may be escaped.
+    let json = json.replace("*/", "*\\/");
+    let range = match find_comment(glsl_src) {
+        None => return Ok(format!("/*{}*/\n{}", json, glsl_src)),
+        Some(range) => range,
+    };
+    let contents = &glsl_src[range.clone()];
+    let start = range.start + (contents.len() - contents.trim_start().len());
+    let end = range.start + contents.trim_end().len();
+    let mut s = String::with_capacity(glsl_src.len() - (end - start) + json.len());
+    s.push_str(&glsl_src[..start]);
+    s.push_str(&json);
+    s.push_str(&glsl_src[end..]);
+    Ok(s)
+}
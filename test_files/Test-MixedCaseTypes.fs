@@ -0,0 +1,45 @@
+/*{
+	"DESCRIPTION": "input types written with the casing of various editors",
+	"ISFVSN": "2.0",
+	"CATEGORIES": [
+		"TEST-GLSL FX"
+	],
+	"INPUTS": [
+		{
+			"NAME": "inputImage",
+			"TYPE": "Image"
+		},
+		{
+			"NAME": "level",
+			"TYPE": "Float",
+			"DEFAULT": 0.5,
+			"MIN": 0.0,
+			"MAX": 1.0
+		},
+		{
+			"NAME": "center",
+			"TYPE": "POINT2D",
+			"DEFAULT": [
+				0.5,
+				0.5
+			]
+		},
+		{
+			"NAME": "fftImage",
+			"TYPE": "audiofft"
+		},
+		{
+			"NAME": "invert",
+			"TYPE": "BOOL",
+			"DEFAULT": 0
+		}
+	]
+}*/
+
+void main()
+{
+	vec4		srcPixel = IMG_NORM_PIXEL(inputImage, center + isf_FragNormCoord - vec2(0.5));
+	float		fft = IMG_NORM_PIXEL(fftImage, vec2(isf_FragNormCoord.x, 0.0)).r;
+	srcPixel.rgb *= level * fft;
+	gl_FragColor = (invert) ? vec4(1.0 - srcPixel.rgb, srcPixel.a) : srcPixel;
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "serde")]
+
+const COMMENTED: &str = r#"/*{
+    "DESCRIPTION": "Comments within the JSON", // Shown in the browser.
+    "INPUTS": [
+        {
+            "NAME": "amount", // Keep this in sync with the body.
+            "TYPE": "float",
+            "DEFAULT": 0.5, // A sensible starting point.
+            "MAX": 1.0
+        },
+        { "NAME": "url", "TYPE": "event", "LABEL": "http://not.a.comment" } // Second input.
+    ] // End of inputs.
+}*/
+
+void main() {
+    gl_FragColor = vec4(amount);
+}
+"#;
+
+#[test]
+fn parse_captures_comments() {
+    let (isf, comments) = isf::parse_with_comments(COMMENTED).unwrap();
+    assert_eq!(isf.inputs.len(), 2);
+    assert_eq!(isf.inputs[1].label.as_deref(), Some("http://not.a.comment"));
+    assert_eq!(comments.get("/DESCRIPTION"), Some("Shown in the browser."));
+    assert_eq!(
+        comments.get("/INPUTS/0/NAME"),
+        Some("Keep this in sync with the body.")
+    );
+    assert_eq!(
+        comments.get("/INPUTS/0/DEFAULT"),
+        Some("A sensible starting point.")
+    );
+    assert_eq!(comments.get("/INPUTS/1"), Some("Second input."));
+    let end = isf::CommentAnchor {
+        pointer: "/INPUTS".to_string(),
+        end: true,
+    };
+    assert_eq!(
+        comments.comments.get(&end).map(|s| &s[..]),
+        Some("End of inputs.")
+    );
+    assert_eq!(comments.comments.len(), 5);
+}
+
+#[test]
+fn comments_roundtrip() {
+    let (isf, comments) = isf::parse_with_comments(COMMENTED).unwrap();
+    let json = isf::to_string_pretty_with_comments(&isf, &comments).unwrap();
+    let glsl_str = format!("/*{}*/", json);
+    let (isf2, comments2) = isf::parse_with_comments(&glsl_str).unwrap();
+    assert_eq!(isf, isf2);
+    assert_eq!(comments2.get("/DESCRIPTION"), Some("Shown in the browser."));
+    assert_eq!(
+        comments2.get("/INPUTS/0/DEFAULT"),
+        Some("A sensible starting point.")
+    );
+    assert_eq!(comments2.get("/INPUTS/1"), Some("Second input."));
+}
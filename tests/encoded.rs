@@ -0,0 +1,29 @@
+#![cfg(feature = "base64")]
+
+#[test]
+fn encoded_roundtrip() {
+    let glsl_str = r#"/*{
+        "DESCRIPTION": "Contains */ which would otherwise end the comment",
+        "INPUTS": [{ "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5 }]
+    }"#;
+    let isf: isf::Isf = serde_json::from_str(&glsl_str[2..]).unwrap();
+    let comment = isf::to_encoded_comment(&isf).unwrap();
+    assert!(comment.starts_with("/*data:application/json;base64,"));
+    assert!(!comment[2..comment.len() - 2].contains("*/"));
+    let glsl_src = format!("{}\nvoid main() {{}}\n", comment);
+    assert_eq!(isf::parse_encoded(&glsl_src).unwrap(), isf);
+}
+
+#[test]
+fn encoded_plain_json() {
+    let glsl_str = r#"/*{ "DESCRIPTION": "Plain" }*/"#;
+    assert_eq!(isf::parse_encoded(glsl_str).unwrap(), isf::parse(glsl_str).unwrap());
+}
+
+#[test]
+fn encoded_invalid() {
+    match isf::parse_encoded("/*data:application/json;base64,!!!*/") {
+        Err(isf::ParseError::Base64 { .. }) => (),
+        res => panic!("expected a base64 error, found {:?}", res),
+    }
+}
@@ -0,0 +1,168 @@
+#![cfg(feature = "serde")]
+
+use isf::{DimContext, DimExpr, EvalError, ExprError, InputValue, PassSizeError};
+
+fn eval(src: &str, ctx: &DimContext) -> Result<f32, EvalError> {
+    DimExpr::parse(src).unwrap().eval(ctx)
+}
+
+#[test]
+fn parse_and_eval() {
+    let ctx = DimContext::new(1920.0, 1080.0).with_input("scale", InputValue::Float(0.25));
+    assert_eq!(eval("$WIDTH/2.0", &ctx), Ok(960.0));
+    assert_eq!(eval("floor($HEIGHT*0.3)", &ctx), Ok(324.0));
+    assert_eq!(eval("max(1.0, $WIDTH * $scale - 100)", &ctx), Ok(380.0));
+    assert_eq!(eval("-(2 + 3) * 4", &ctx), Ok(-20.0));
+    assert_eq!(eval("ceil(min($WIDTH, $HEIGHT) / 7)", &ctx), Ok(155.0));
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(DimExpr::parse("$WIDTH /"), Err(ExprError::UnexpectedEnd));
+    assert_eq!(
+        DimExpr::parse("$WIDTH % 2"),
+        Err(ExprError::UnexpectedChar { pos: 7, ch: '%' })
+    );
+    assert_eq!(
+        DimExpr::parse("$WIDTH $HEIGHT"),
+        Err(ExprError::UnexpectedToken { pos: 7 })
+    );
+    assert_eq!(
+        DimExpr::parse("round($WIDTH)"),
+        Err(ExprError::UnknownFunction {
+            name: "round".to_string()
+        })
+    );
+    assert!(matches!(
+        DimExpr::parse("max($WIDTH)"),
+        Err(ExprError::ArgCount {
+            expected: 2,
+            found: 1,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn eval_errors() {
+    let ctx = DimContext::new(640.0, 480.0).with_input("zero", InputValue::Long(0));
+    assert_eq!(eval("$WIDTH / $zero", &ctx), Err(EvalError::DivisionByZero));
+    assert_eq!(
+        eval("$WIDTH * $scale", &ctx),
+        Err(EvalError::UndefinedVariable {
+            name: "scale".to_string()
+        })
+    );
+    // Calls constructed directly are checked against the function's arity.
+    let call = DimExpr::Call(isf::Func::Max, vec![DimExpr::Num(1.0)]);
+    assert_eq!(
+        call.eval(&ctx),
+        Err(EvalError::ArgCount {
+            func: isf::Func::Max,
+            expected: 2,
+            found: 1,
+        })
+    );
+}
+
+#[test]
+fn pass_sizes_reference_targets() {
+    // The first pass is sized relative to the second, so must be resolved after it.
+    let isf = isf::parse(
+        r#"/*{
+        "PASSES": [
+            { "TARGET": "blur", "WIDTH": "$down.width / 2", "HEIGHT": "$down.height / 2" },
+            { "TARGET": "down", "WIDTH": "floor($WIDTH / 3)", "HEIGHT": "$HEIGHT / 4" },
+            {}
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let width = isf.passes[0].width_expr().unwrap().unwrap();
+    assert_eq!(
+        width.variables().into_iter().collect::<Vec<_>>(),
+        vec!["down.width"]
+    );
+    let sizes = isf.pass_sizes(&DimContext::new(1000.0, 600.0)).unwrap();
+    assert_eq!(sizes, vec![[166, 75], [333, 150], [1000, 600]]);
+}
+
+#[test]
+fn pass_sizes_errors() {
+    let isf = isf::parse(
+        r#"/*{
+        "PASSES": [
+            { "TARGET": "a", "WIDTH": "$b.width" },
+            { "TARGET": "b", "WIDTH": "$a.width" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    match isf.pass_sizes(&DimContext::new(100.0, 100.0)) {
+        Err(PassSizeError::Cycle { err }) => assert_eq!(err.passes, vec![0, 1]),
+        res => panic!("expected a cycle, found {:?}", res),
+    }
+
+    let isf = isf::parse(r#"/*{ "PASSES": [{ "WIDTH": "$missing.width" }] }*/"#).unwrap();
+    assert!(matches!(
+        isf.pass_sizes(&DimContext::new(100.0, 100.0)),
+        Err(PassSizeError::Eval { pass: 0, .. })
+    ));
+}
+
+#[test]
+fn pass_dimension_ast() {
+    use isf::{BinOp, Func};
+    let isf = isf::parse(
+        r#"/*{
+        "PASSES": [
+            { "TARGET": "half", "WIDTH": "$WIDTH/2.0", "HEIGHT": "floor($HEIGHT*$scale)" },
+            {}
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let var = |name: &str| Box::new(DimExpr::Var(name.to_string()));
+    let width = isf.passes[0].width_expr().unwrap().unwrap();
+    assert_eq!(
+        width,
+        DimExpr::Binary(BinOp::Div, var("WIDTH"), Box::new(DimExpr::Num(2.0)))
+    );
+    let height = isf.passes[0].height_expr().unwrap().unwrap();
+    assert_eq!(
+        height,
+        DimExpr::Call(
+            Func::Floor,
+            vec![DimExpr::Binary(BinOp::Mul, var("HEIGHT"), var("scale"))]
+        )
+    );
+    let vars: Vec<_> = height.variables().into_iter().collect();
+    assert_eq!(vars, vec!["HEIGHT", "scale"]);
+    // The source string remains the source of truth and may be parsed again after display.
+    assert_eq!(DimExpr::parse(&height.to_string()), Ok(height));
+    assert_eq!(isf.passes[1].width_expr(), Ok(None));
+}
+
+#[test]
+fn eval_with_input_defaults() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "scale", "TYPE": "float", "DEFAULT": 0.5 },
+            { "NAME": "taps", "TYPE": "long", "DEFAULT": 4 },
+            { "NAME": "center", "TYPE": "point2D", "DEFAULT": [0.5, 0.5] }
+        ],
+        "PASSES": [{ "TARGET": "a", "WIDTH": "$WIDTH * $scale", "HEIGHT": "$HEIGHT / $taps" }]
+    }*/"#,
+    )
+    .unwrap();
+    let ctx = DimContext::new(640.0, 480.0)
+        .with_input("taps", InputValue::Long(2))
+        .with_defaults(&isf);
+    assert_eq!(ctx.inputs["scale"], InputValue::Float(0.5));
+    // Values provided by the host take precedence over the defaults.
+    assert_eq!(ctx.inputs["taps"], InputValue::Long(2));
+    assert!(!ctx.inputs.contains_key("center"));
+    assert_eq!(isf.pass_sizes(&ctx), Ok(vec![[320, 240]]));
+    assert_eq!(eval("$WIDTH / ($taps - 2)", &ctx), Err(EvalError::DivisionByZero));
+}
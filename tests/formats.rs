@@ -0,0 +1,42 @@
+// Roundtrip every test file through each of the optional formats.
+#![cfg(any(feature = "toml", feature = "yaml"))]
+
+fn test_file_isfs() -> Vec<(std::path::PathBuf, isf::Isf)> {
+    let test_files_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files");
+    let mut isfs = vec![];
+    for entry in std::fs::read_dir(test_files_path).unwrap() {
+        let path = entry.unwrap().path();
+        let ext = path.extension().and_then(|s| s.to_str());
+        if ext == Some("fs") || ext == Some("vs") {
+            let glsl_str = std::fs::read_to_string(&path).unwrap();
+            if let Ok(isf) = isf::parse(&glsl_str) {
+                isfs.push((path, isf));
+            }
+        }
+    }
+    isfs
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn roundtrip_toml() {
+    for (path, isf) in test_file_isfs() {
+        let s = isf::to_toml(&isf)
+            .unwrap_or_else(|err| panic!("failed to serialize {}: {}", path.display(), err));
+        let isf2 = isf::from_toml(&s)
+            .unwrap_or_else(|err| panic!("failed to deserialize {}: {}", path.display(), err));
+        assert_eq!(isf, isf2, "{}", path.display());
+    }
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn roundtrip_yaml() {
+    for (path, isf) in test_file_isfs() {
+        let s = isf::to_yaml(&isf)
+            .unwrap_or_else(|err| panic!("failed to serialize {}: {}", path.display(), err));
+        let isf2 = isf::from_yaml(&s)
+            .unwrap_or_else(|err| panic!("failed to deserialize {}: {}", path.display(), err));
+        assert_eq!(isf, isf2, "{}", path.display());
+    }
+}
@@ -0,0 +1,140 @@
+use isf::glsl::{self, GlslUniform};
+
+fn uniform(ty: &str, name: &str, precision: Option<&str>, array_len: Option<&str>) -> GlslUniform {
+    GlslUniform {
+        name: name.to_string(),
+        ty: ty.to_string(),
+        precision: precision.map(str::to_string),
+        array_len: array_len.map(str::to_string),
+    }
+}
+
+#[test]
+fn declared_uniforms() {
+    let src = r#"
+        #version 330
+        #define uniform_count 4
+        // uniform float commented;
+        /* uniform float alsoCommented; */
+        uniform float amount;
+        uniform highp vec2 center, offsets[4];
+        layout(std140) uniform Block { float inBlock; } block;
+        uniform int[2] pair;
+        uniform sampler2D inputImage;
+        float notUniform;
+        uniform float withInit = 1.0, other;
+        void main() { float x = amount; }
+        uniform lowp vec4 tint;
+    "#;
+    assert_eq!(
+        glsl::declared_uniforms(src),
+        vec![
+            uniform("float", "amount", None, None),
+            uniform("vec2", "center", Some("highp"), None),
+            uniform("vec2", "offsets", Some("highp"), Some("4")),
+            uniform("int", "pair", None, Some("2")),
+            uniform("sampler2D", "inputImage", None, None),
+            uniform("float", "withInit", None, None),
+            uniform("float", "other", None, None),
+            uniform("vec4", "tint", Some("lowp"), None),
+        ]
+    );
+}
+
+#[test]
+fn replace_identifier() {
+    let src = "// amount\nfloat a = amount * pos.amount + amounts;";
+    assert_eq!(
+        glsl::replace_identifier(src, "amount", "0.5"),
+        "// amount\nfloat a = 0.5 * pos.amount + amounts;"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn bake_constants() {
+    use isf::InputValue;
+    use std::collections::BTreeMap;
+    let glsl_str = r#"/*{
+    "INPUTS": [
+        { "NAME": "inputImage", "TYPE": "image" },
+        { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5 },
+        { "NAME": "offset", "TYPE": "point2D" },
+        { "NAME": "tint", "TYPE": "color", "DEFAULT": [1.0, 0.5, 0.0] },
+        { "NAME": "invert", "TYPE": "bool" }
+    ]
+}*/
+void main() {
+    vec4 c = IMG_NORM_PIXEL(inputImage, isf_FragNormCoord + offset);
+    c.rgb = mix(c.rgb, tint, amount);
+    gl_FragColor = invert ? 1.0 - c : c;
+}
+"#;
+    let (mut isf, body) = isf::parse_with_source(glsl_str).unwrap();
+    let mut body = body.src.to_string();
+    let mut values = BTreeMap::new();
+    values.insert("inputImage".to_string(), InputValue::Image);
+    values.insert("amount".to_string(), InputValue::Float(1.0));
+    values.insert("offset".to_string(), InputValue::Point2d([-0.5, 0.25]));
+    values.insert("tint".to_string(), InputValue::Color([1.0, 0.5, 0.0, 1.0]));
+    values.insert("invert".to_string(), InputValue::Long(1));
+    isf.bake_constants(&values, &mut body);
+    let names: Vec<_> = isf.inputs.iter().map(|i| &i.name[..]).collect();
+    assert_eq!(names, vec!["inputImage", "invert"]);
+    assert_eq!(
+        body,
+        r#"
+void main() {
+    vec4 c = IMG_NORM_PIXEL(inputImage, isf_FragNormCoord + vec2(-0.5, 0.25));
+    c.rgb = mix(c.rgb, vec3(1.0, 0.5, 0.0), 1.0);
+    gl_FragColor = invert ? 1.0 - c : c;
+}
+"#
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn bake_constants_removes_declarations() {
+    use isf::InputValue;
+    use std::collections::BTreeMap;
+    let glsl_str = r#"/*{
+    "INPUTS": [
+        { "NAME": "amount", "TYPE": "float" },
+        { "NAME": "gain", "TYPE": "float" },
+        { "NAME": "level", "TYPE": "float" }
+    ]
+}*/
+uniform float amount; // amount
+uniform float gain, level;
+void main() {
+    gl_FragColor = vec4(amount * gain * level);
+}
+"#;
+    let (mut isf, body) = isf::parse_with_source(glsl_str).unwrap();
+    let mut body = body.src.to_string();
+    let mut values = BTreeMap::new();
+    values.insert("amount".to_string(), InputValue::Float(0.5));
+    values.insert("gain".to_string(), InputValue::Float(2.0));
+    isf.bake_constants(&values, &mut body);
+    let names: Vec<_> = isf.inputs.iter().map(|i| &i.name[..]).collect();
+    assert_eq!(names, vec!["level"]);
+    assert_eq!(
+        body,
+        r#"
+uniform float level;
+void main() {
+    gl_FragColor = vec4(0.5 * 2.0 * level);
+}
+"#
+    );
+
+    // The last of several declarators is removed along with the preceding comma.
+    let (mut isf, body) = isf::parse_with_source(glsl_str).unwrap();
+    let mut body = body.src.to_string();
+    let mut values = BTreeMap::new();
+    values.insert("level".to_string(), InputValue::Float(1.0));
+    isf.bake_constants(&values, &mut body);
+    assert!(body.contains("\nuniform float gain;\n"));
+    assert!(body.contains("vec4(amount * gain * 1.0)"));
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+const SHADER: &str = r#"/*{
+    "INPUTS": [{ "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5 }],
+    "IMPORTED": { "noise": { "PATH": "noise.png" } }
+}*/
+void main() { gl_FragColor = IMG_THIS_PIXEL(noise) * amount; }
+"#;
+
+fn imports(bytes: &[u8]) -> BTreeMap<String, Vec<u8>> {
+    let mut map = BTreeMap::new();
+    map.insert("noise".to_string(), bytes.to_vec());
+    map
+}
+
+#[test]
+fn resolved_shader_hash() {
+    let (isf, body) = isf::parse_with_source(SHADER).unwrap();
+    let hash = isf::resolved_shader_hash(&isf, body.src, &imports(&[1, 2, 3]));
+
+    // The hash is stable.
+    assert_eq!(
+        hash,
+        isf::resolved_shader_hash(&isf, body.src, &imports(&[1, 2, 3]))
+    );
+
+    // Changes to the import bytes, body or metadata all change the hash.
+    assert_ne!(
+        hash,
+        isf::resolved_shader_hash(&isf, body.src, &imports(&[1, 2, 4]))
+    );
+    assert_ne!(
+        hash,
+        isf::resolved_shader_hash(&isf, body.src, &imports(&[]))
+    );
+    assert_ne!(
+        hash,
+        isf::resolved_shader_hash(&isf, body.src, &BTreeMap::new())
+    );
+    assert_ne!(
+        hash,
+        isf::resolved_shader_hash(&isf, "void main() {}", &imports(&[1, 2, 3]))
+    );
+    let mut isf2 = isf.clone();
+    isf2.description = Some("Changed".to_string());
+    assert_ne!(
+        hash,
+        isf::resolved_shader_hash(&isf2, body.src, &imports(&[1, 2, 3]))
+    );
+    assert_ne!(isf.metadata_hash(), isf2.metadata_hash());
+
+    // Unrelated import bytes are ignored.
+    let mut extra = imports(&[1, 2, 3]);
+    extra.insert("unused".to_string(), vec![4, 5, 6]);
+    assert_eq!(hash, isf::resolved_shader_hash(&isf, body.src, &extra));
+}
+
+#[test]
+fn metadata_hash_ignores_formatting() {
+    let a = isf::parse(r#"/*{ "DESCRIPTION": "A", "CATEGORIES": ["Blur"] }*/"#).unwrap();
+    let b = isf::parse("/*{\n  \"CATEGORIES\" : [ \"Blur\" ],\n  \"DESCRIPTION\" : \"A\"\n}*/")
+        .unwrap();
+    assert_eq!(a.metadata_hash(), b.metadata_hash());
+}
+
+#[cfg(unix)]
+#[test]
+fn hash_non_unicode_import_paths() {
+    use std::os::unix::ffi::OsStrExt;
+    let (mut isf, body) = isf::parse_with_source(SHADER).unwrap();
+    let hash = isf.metadata_hash();
+    let path = std::ffi::OsStr::from_bytes(b"noise\xff.png").into();
+    isf.imported.insert("noise".to_string(), isf::ImageImport { path });
+    assert_ne!(isf.metadata_hash(), hash);
+    let resolved = isf::resolved_shader_hash(&isf, body.src, &imports(&[1, 2, 3]));
+
+    // Distinct paths that would be equal if converted lossily hash differently.
+    let mut isf2 = isf.clone();
+    let path = std::ffi::OsStr::from_bytes(b"noise\xfe.png").into();
+    isf2.imported.insert("noise".to_string(), isf::ImageImport { path });
+    assert_ne!(isf.metadata_hash(), isf2.metadata_hash());
+    assert_ne!(
+        resolved,
+        isf::resolved_shader_hash(&isf2, body.src, &imports(&[1, 2, 3]))
+    );
+}
@@ -0,0 +1,774 @@
+#![cfg(feature = "serde")]
+
+fn parse_inputs(json: &str) -> Vec<isf::Input> {
+    let glsl_str = format!("/*{}*/", json);
+    isf::parse(&glsl_str).unwrap().inputs
+}
+
+fn long(input: &isf::Input) -> &isf::InputLong {
+    match input.ty {
+        isf::InputType::Long(ref long) => long,
+        ref ty => panic!("expected long input, found {:?}", ty),
+    }
+}
+
+// Negative enum values are commonly used as sentinels (e.g. -1 for "auto").
+#[test]
+fn long_negative_values() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [{
+                "NAME": "mode",
+                "TYPE": "long",
+                "DEFAULT": -1,
+                "VALUES": [-1, 0, 1],
+                "LABELS": ["Auto", "Off", "On"]
+            }]
+        }"#,
+    );
+    let mode = long(&inputs[0]);
+    assert_eq!(mode.values, vec![-1, 0, 1]);
+    assert_eq!(mode.default, Some(-1));
+    assert_eq!(mode.default_index(), Some(0));
+    assert_eq!(mode.value_for_label("Auto"), Some(-1));
+    assert_eq!(mode.label_for_value(-1), Some("Auto"));
+    assert_eq!(mode.label_for_value(-2), None);
+
+    // Negative values must survive a roundtrip.
+    let json = serde_json::to_string(&inputs).unwrap();
+    let inputs2: Vec<isf::Input> = serde_json::from_str(&json).unwrap();
+    assert_eq!(inputs, inputs2);
+}
+
+#[test]
+fn long_mismatched_labels() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [{
+                "NAME": "mode",
+                "TYPE": "long",
+                "VALUES": [-2, -1, 0],
+                "LABELS": ["A", "B"]
+            }]
+        }"#,
+    );
+    let mode = long(&inputs[0]);
+    assert_eq!(mode.default_index(), None);
+    assert_eq!(mode.label_for_value(-1), Some("B"));
+    assert_eq!(mode.label_for_value(0), None);
+    assert_eq!(mode.value_for_label("B"), Some(-1));
+}
+
+#[test]
+fn numeric_bounds() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5, "MIN": -1.0, "MAX": 2.0 },
+                { "NAME": "count", "TYPE": "long", "DEFAULT": 3, "MIN": 1, "MAX": 10 },
+                { "NAME": "half", "TYPE": "float", "MIN": 0.0 },
+                { "NAME": "center", "TYPE": "point2D", "MIN": [0, 0], "MAX": [1, 1] }
+            ]
+        }"#,
+    );
+    assert_eq!(inputs[0].numeric_bounds(), Some((-1.0, 2.0)));
+    assert_eq!(inputs[0].numeric_default(), Some(0.5));
+    assert_eq!(inputs[1].numeric_bounds(), Some((1.0, 10.0)));
+    assert_eq!(inputs[1].numeric_default(), Some(3.0));
+    assert_eq!(inputs[2].numeric_bounds(), None);
+    assert_eq!(inputs[3].numeric_bounds(), None);
+    assert_eq!(inputs[3].numeric_default(), None);
+}
+
+fn float(input: &isf::Input) -> &isf::InputFloat {
+    match input.ty {
+        isf::InputType::Float(ref f) => f,
+        ref ty => panic!("expected float input, found {:?}", ty),
+    }
+}
+
+#[test]
+fn explode_scalars() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                {
+                    "NAME": "center",
+                    "LABEL": "Center",
+                    "TYPE": "point2D",
+                    "DEFAULT": [0.25, 0.75],
+                    "MIN": [0, -1],
+                    "MAX": [1, 2]
+                },
+                { "NAME": "tint", "TYPE": "color", "DEFAULT": [1.0, 0.5, 0.25] },
+                { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5 }
+            ]
+        }"#,
+    );
+
+    let center = inputs[0].explode_scalars();
+    let names: Vec<_> = center.iter().map(|i| &i.name[..]).collect();
+    assert_eq!(names, vec!["center.x", "center.y"]);
+    assert_eq!(center[1].label.as_deref(), Some("Center.y"));
+    let y = float(&center[1]);
+    assert_eq!((y.default, y.min, y.max), (Some(0.75), Some(-1.0), Some(2.0)));
+
+    let tint = inputs[1].explode_scalars();
+    let names: Vec<_> = tint.iter().map(|i| &i.name[..]).collect();
+    assert_eq!(names, vec!["tint.r", "tint.g", "tint.b"]);
+    let g = float(&tint[1]);
+    assert_eq!((g.default, g.min, g.max), (Some(0.5), Some(0.0), Some(1.0)));
+
+    assert_eq!(inputs[2].explode_scalars(), vec![inputs[2].clone()]);
+}
+
+// Every listed type name must be handled by the deserializer and serialize back to itself.
+#[test]
+fn all_type_names() {
+    assert_eq!(isf::InputType::ALL_TYPE_NAMES.len(), 9);
+    for &name in isf::InputType::ALL_TYPE_NAMES {
+        let json = format!(r#"{{ "NAME": "x", "TYPE": "{}" }}"#, name);
+        let input: isf::Input = serde_json::from_str(&json).unwrap();
+        let value = serde_json::to_value(&input).unwrap();
+        assert_eq!(value["TYPE"], name);
+    }
+}
+
+#[test]
+fn unknown_type_lists_valid_names() {
+    let json = r#"{ "NAME": "x", "TYPE": "vec3" }"#;
+    let err = serde_json::from_str::<isf::Input>(json).unwrap_err().to_string();
+    assert!(err.contains("vec3"));
+    assert!(err.contains("point2D"));
+}
+
+#[test]
+fn unknown_type_names_input_and_type() {
+    let glsl_str = r#"/*{ "INPUTS": [{ "NAME": "amount", "TYPE": "panic_type" }] }*/"#;
+    let err = isf::parse(glsl_str).unwrap_err().to_string();
+    assert!(err.contains(r#"unknown ISF input type "panic_type""#), "{}", err);
+    assert!(err.contains(r#"input "amount""#), "{}", err);
+}
+
+#[test]
+fn fluent_modifiers() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                { "NAME": "amount", "TYPE": "float" },
+                { "NAME": "count", "TYPE": "long" }
+            ]
+        }"#,
+    );
+    let amount = inputs[0].clone().with_name("level").with_label("Level");
+    assert_eq!(amount.name, "level");
+    assert_eq!(amount.label.as_deref(), Some("Level"));
+
+    let level = float(&amount).clone().with_default(0.5).with_range(0.0, 2.0);
+    assert_eq!((level.default, level.min, level.max), (Some(0.5), Some(0.0), Some(2.0)));
+
+    let count = long(&inputs[1]).clone().with_default(2).with_range(1, 4);
+    assert_eq!((count.default, count.min, count.max), (Some(2), Some(1), Some(4)));
+
+    let flag = isf::InputBool { default: None }.with_default(true);
+    assert_eq!(flag.default, Some(true));
+}
+
+fn point(input: &isf::Input) -> &isf::InputPoint2d {
+    match input.ty {
+        isf::InputType::Point2d(ref point) => point,
+        ref ty => panic!("expected point2D input, found {:?}", ty),
+    }
+}
+
+#[test]
+fn point_coordinate_space() {
+    use isf::{CoordinateSpace, InputValue};
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                { "NAME": "center", "TYPE": "point2D", "DEFAULT": [0.5, 0.25] },
+                {
+                    "NAME": "origin",
+                    "TYPE": "point2D",
+                    "DEFAULT": [320, 120],
+                    "COORDINATE_SPACE": "pixel"
+                }
+            ]
+        }"#,
+    );
+    let (center, origin) = (point(&inputs[0]), point(&inputs[1]));
+    assert_eq!(center.coordinate_space, CoordinateSpace::Normalized);
+    assert_eq!(origin.coordinate_space, CoordinateSpace::Pixel);
+
+    let size = [640.0, 480.0];
+    assert_eq!(
+        center.default_value(CoordinateSpace::Pixel, size),
+        Some(InputValue::Point2d([320.0, 120.0]))
+    );
+    assert_eq!(
+        origin.default_value(CoordinateSpace::Normalized, size),
+        Some(InputValue::Point2d([0.5, 0.25]))
+    );
+    assert_eq!(
+        origin.default_value(CoordinateSpace::Pixel, size),
+        Some(InputValue::Point2d([320.0, 120.0]))
+    );
+
+    // Only the non-default space is written.
+    let json = serde_json::to_string(&inputs).unwrap();
+    assert_eq!(json.matches("COORDINATE_SPACE").count(), 1);
+    assert!(json.contains(r#""COORDINATE_SPACE":"pixel""#));
+    let roundtrip: Vec<isf::Input> = serde_json::from_str(&json).unwrap();
+    assert_eq!(roundtrip, inputs);
+}
+
+#[test]
+fn identity_accessors() {
+    use isf::{CoordinateSpace, InputValue};
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                {
+                    "NAME": "tint",
+                    "TYPE": "color",
+                    "DEFAULT": [1.0, 0.5, 0.0],
+                    "IDENTITY": [1.0, 1.0, 1.0, 0.5]
+                },
+                {
+                    "NAME": "offset",
+                    "TYPE": "point2D",
+                    "DEFAULT": [0.25, 0.5],
+                    "IDENTITY": [0.0, 0.0]
+                },
+                { "NAME": "gain", "TYPE": "float", "IDENTITY": 1.0 },
+                { "NAME": "bias", "TYPE": "float" }
+            ]
+        }"#,
+    );
+    let tint = match inputs[0].ty {
+        isf::InputType::Color(ref c) => c,
+        ref ty => panic!("expected color input, found {:?}", ty),
+    };
+    assert_eq!(tint.default_rgba(), Some([1.0, 0.5, 0.0, 1.0]));
+    assert_eq!(tint.identity_rgba(), Some([1.0, 1.0, 1.0, 0.5]));
+
+    let offset = point(&inputs[1]);
+    let size = [100.0, 100.0];
+    assert_eq!(
+        offset.identity_value(CoordinateSpace::Normalized, size),
+        Some(InputValue::Point2d([0.0, 0.0]))
+    );
+    assert_eq!(offset.identity_or(isf::Point2d::new(1.0, 1.0)), isf::Point2d::new(0.0, 0.0));
+
+    assert_eq!(float(&inputs[2]).identity_or(0.0), 1.0);
+    assert_eq!(float(&inputs[3]).identity_or(0.0), 0.0);
+}
+
+#[test]
+fn long_add_remove_option() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [{
+                "NAME": "mode",
+                "TYPE": "long",
+                "DEFAULT": 1,
+                "VALUES": [0, 1, 2],
+                "LABELS": ["Off", "On"]
+            }]
+        }"#,
+    );
+    let mut mode = long(&inputs[0]).clone();
+    mode.add_option(3, "Auto");
+    assert_eq!(mode.values, vec![0, 1, 2, 3]);
+    assert_eq!(mode.labels, vec!["Off", "On", "2", "Auto"]);
+    mode.add_option(2, "Both");
+    assert_eq!(mode.label_for_value(2), Some("Both"));
+    assert_eq!(mode.values.len(), 4);
+
+    assert!(mode.remove_option(1));
+    assert!(!mode.remove_option(1));
+    assert_eq!(mode.values, vec![0, 2, 3]);
+    assert_eq!(mode.labels, vec!["Off", "Both", "Auto"]);
+    assert_eq!(mode.default, Some(0));
+}
+
+#[test]
+fn retype() {
+    use isf::InputTypeKind;
+    let mut inputs = parse_inputs(
+        r#"{
+            "INPUTS": [{
+                "NAME": "amount",
+                "TYPE": "float",
+                "LABEL": "Amount",
+                "DEFAULT": 0.5,
+                "MAX": 2.0
+            }]
+        }"#,
+    );
+    let amount = &mut inputs[0];
+    assert!(!amount.type_changed(InputTypeKind::Float));
+    amount.retype(InputTypeKind::Float);
+    assert_eq!(float(amount).default, Some(0.5));
+
+    assert!(amount.type_changed(InputTypeKind::Long));
+    amount.retype(InputTypeKind::Long);
+    assert_eq!(amount.name, "amount");
+    assert_eq!(amount.label.as_deref(), Some("Amount"));
+    assert_eq!(amount.ty.kind(), InputTypeKind::Long);
+    assert_eq!(long(amount).default, None);
+    assert_eq!(long(amount).max, None);
+    assert!(long(amount).values.is_empty());
+}
+
+#[test]
+fn help_text() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                {
+                    "NAME": "amount",
+                    "TYPE": "float",
+                    "HELP": "The strength of the effect, where 0 disables it."
+                },
+                { "NAME": "center", "TYPE": "point2D" }
+            ]
+        }"#,
+    );
+    assert_eq!(
+        inputs[0].help(),
+        Some("The strength of the effect, where 0 disables it.")
+    );
+    assert_eq!(inputs[1].help(), None);
+    let json = serde_json::to_string(&inputs).unwrap();
+    assert_eq!(json.matches("HELP").count(), 1);
+    let roundtrip: Vec<isf::Input> = serde_json::from_str(&json).unwrap();
+    assert_eq!(roundtrip, inputs);
+}
+
+#[test]
+fn type_str_matches_serialized_type() {
+    use isf::InputTypeKind::*;
+    let kinds = [Event, Bool, Long, Float, Point2d, Color, Image, Audio, AudioFft];
+    for (kind, name) in kinds.iter().zip(isf::InputType::ALL_TYPE_NAMES) {
+        let input = isf::Input::builder("input", kind.default_type()).build();
+        let json = serde_json::to_value(&input).unwrap();
+        assert_eq!(json["TYPE"], input.ty.type_str());
+        assert_eq!(input.ty.type_str(), *name);
+    }
+}
+
+#[test]
+fn input_type_kind_from_str() {
+    for name in isf::InputType::ALL_TYPE_NAMES {
+        let kind: isf::InputTypeKind = name.parse().unwrap();
+        assert_eq!(kind.default_type().type_str(), *name);
+    }
+    let kind = "POINT2D".parse::<isf::InputTypeKind>().unwrap();
+    assert_eq!(kind, isf::InputTypeKind::Point2d);
+    let err = "point3D".parse::<isf::InputTypeKind>().unwrap_err();
+    assert_eq!(err.ty, "point3D");
+    assert!(err.to_string().contains("point2D"));
+}
+
+#[test]
+fn color_struct() {
+    use isf::Color;
+    let rgb: Color = serde_json::from_str("[1.0, 0.5, 0.0]").unwrap();
+    assert_eq!(rgb, Color::new(1.0, 0.5, 0.0, 1.0));
+    let rgba: Color = serde_json::from_str("[1.0, 0.5, 0.0, 0.25]").unwrap();
+    assert_eq!(rgba.to_array(), [1.0, 0.5, 0.0, 0.25]);
+    assert_eq!(serde_json::to_string(&rgb).unwrap(), "[1.0,0.5,0.0,1.0]");
+    let err = serde_json::from_str::<Color>("[1.0, 0.5]").unwrap_err();
+    assert!(err.to_string().contains("found 2"));
+
+    let inputs = parse_inputs(
+        r#"{
+        "INPUTS": [
+            { "NAME": "tint", "TYPE": "color", "DEFAULT": [1, 0, 0], "MAX": [1, 1, 1] },
+            { "NAME": "fade", "TYPE": "color", "DEFAULT": [1, 0, 0], "MAX": [1, 1, 1, 0.5] }
+        ]
+    }"#,
+    );
+    match inputs[0].ty {
+        isf::InputType::Color(ref c) => {
+            assert_eq!(c.default, Some(Color::rgb(1.0, 0.0, 0.0)));
+            assert_eq!(c.max, Some(Color::rgb(1.0, 1.0, 1.0)));
+            assert_eq!(c.identity, None);
+            assert!(c.rgb);
+        }
+        _ => panic!("expected a color input"),
+    }
+    match inputs[1].ty {
+        isf::InputType::Color(ref c) => assert!(!c.rgb),
+        _ => panic!("expected a color input"),
+    }
+
+    // RGB colors are written with three components again, and RGBA colors with four.
+    let json = serde_json::to_value(&inputs).unwrap();
+    assert_eq!(json[0]["DEFAULT"], serde_json::json!([1.0, 0.0, 0.0]));
+    assert_eq!(json[1]["DEFAULT"], serde_json::json!([1.0, 0.0, 0.0, 1.0]));
+    assert_eq!(json[1]["MAX"], serde_json::json!([1.0, 1.0, 1.0, 0.5]));
+    let reparsed: Vec<isf::Input> = serde_json::from_value(json).unwrap();
+    assert_eq!(reparsed, inputs);
+
+    let src = r#"/*{ "INPUTS": [{ "NAME": "tint", "TYPE": "color", "MAX": [1, 1] }] }*/"#;
+    let err = isf::parse(src).unwrap_err();
+    assert!(err.to_string().contains("found 2"), "{}", err);
+}
+
+#[test]
+fn color_to_rgba() {
+    assert_eq!(isf::color_to_rgba(&[1.0, 0.5, 0.0]), Ok([1.0, 0.5, 0.0, 1.0]));
+    assert_eq!(
+        isf::color_to_rgba(&[1.0, 0.5, 0.0, 0.25]),
+        Ok([1.0, 0.5, 0.0, 0.25])
+    );
+    assert_eq!(
+        isf::color_to_rgba(&[1.0, 0.5]),
+        Err(isf::ColorError { components: 2 })
+    );
+    assert!(isf::color_to_rgba(&[0.0; 5]).is_err());
+}
+
+#[test]
+fn hex_colors() {
+    let inputs = parse_inputs(
+        r##"{
+        "INPUTS": [
+            { "NAME": "tint", "TYPE": "color", "DEFAULT": "#ff8800", "MAX": "#FFFFFF80" },
+            { "NAME": "mixed", "TYPE": "color", "DEFAULT": "#000000", "MAX": [1, 1, 1] }
+        ]
+    }"##,
+    );
+    match inputs[0].ty {
+        isf::InputType::Color(ref c) => {
+            assert_eq!(c.default, Some(isf::Color::rgb(1.0, 136.0 / 255.0, 0.0)));
+            assert_eq!(c.max, Some(isf::Color::new(1.0, 1.0, 1.0, 128.0 / 255.0)));
+            assert!(!c.rgb);
+        }
+        _ => panic!("expected a color input"),
+    }
+    // Hex values are normalized to arrays upon serialization.
+    let json = serde_json::to_value(&inputs[1]).unwrap();
+    assert_eq!(json["DEFAULT"], serde_json::json!([0.0, 0.0, 0.0]));
+    assert_eq!(
+        isf::Color::from_hex("#ff000080").unwrap(),
+        isf::Color::new(1.0, 0.0, 0.0, 128.0 / 255.0)
+    );
+
+    for (hex, err) in &[
+        ("#ff88", "must have 6 (RGB) or 8 (RGBA) digits"),
+        ("#ff88zz", "non-hex digit `z`"),
+        ("ff8800", "must begin with `#`"),
+    ] {
+        let json = format!(
+            r#"/*{{ "INPUTS": [{{ "NAME": "tint", "TYPE": "color", "DEFAULT": "{}" }}] }}*/"#,
+            hex
+        );
+        let msg = isf::parse(&json).unwrap_err().to_string();
+        assert!(msg.contains(err), "{}", msg);
+    }
+}
+
+#[test]
+fn audio_max_roundtrip() {
+    let inputs = parse_inputs(
+        r#"{
+        "INPUTS": [
+            { "NAME": "wave", "TYPE": "audio", "MAX": 256 },
+            { "NAME": "fft", "TYPE": "audioFFT", "MAX": 512 },
+            { "NAME": "plain", "TYPE": "audio" }
+        ]
+    }"#,
+    );
+    match (&inputs[0].ty, &inputs[1].ty, &inputs[2].ty) {
+        (
+            isf::InputType::Audio(wave),
+            isf::InputType::AudioFft(fft),
+            isf::InputType::Audio(plain),
+        ) => {
+            assert_eq!(wave.num_samples, Some(256));
+            assert_eq!(fft.num_columns, Some(512));
+            assert_eq!(plain.num_samples, None);
+        }
+        _ => panic!("expected audio inputs"),
+    }
+    for (input, max) in inputs.iter().zip(&[Some(256), Some(512), None]) {
+        let json = serde_json::to_value(input).unwrap();
+        assert_eq!(json["MAX"].as_u64(), *max);
+        assert!(json.get("MIN").is_none() || json["MIN"].is_null());
+        let input2: isf::Input = serde_json::from_value(json).unwrap();
+        assert_eq!(*input, input2);
+    }
+}
+
+#[test]
+fn point2d_struct() {
+    use isf::Point2d;
+    let p: Point2d = serde_json::from_str("[0.25, 0.75]").unwrap();
+    assert_eq!((p.x, p.y), (0.25, 0.75));
+    assert_eq!(p.to_array(), [0.25, 0.75]);
+    assert_eq!(Point2d::from_array([0.25, 0.75]), p);
+    assert_eq!(serde_json::to_string(&p).unwrap(), "[0.25,0.75]");
+
+    let glsl = r#"/*{ "INPUTS": [{ "NAME": "pos", "TYPE": "point2D", "DEFAULT": [1, 2, 3] }] }*/"#;
+    let msg = isf::parse(glsl).unwrap_err().to_string();
+    assert!(msg.contains("expected a point with 2 components, found 3"), "{}", msg);
+}
+
+#[test]
+fn long_extra_labels() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [{
+                "NAME": "mode",
+                "TYPE": "long",
+                "VALUES": [0, 1],
+                "LABELS": ["Off", "On", "Extra"]
+            }]
+        }"#,
+    );
+    let mode = long(&inputs[0]);
+    assert_eq!(mode.value_for_label("On"), Some(1));
+    assert_eq!(mode.value_for_label("Extra"), None);
+    assert_eq!(mode.value_for_label("Missing"), None);
+    let isf = isf::parse(r#"/*{ "INPUTS": [{ "NAME": "mode", "TYPE": "long", "VALUES": [0, 1],
+        "LABELS": ["Off", "On", "Extra"] }] }*/"#)
+    .unwrap();
+    assert!(matches!(
+        isf.validate().unwrap_err()[..],
+        [isf::ValidationError::LongLabelCountMismatch { values: 2, labels: 3, .. }]
+    ));
+}
+
+#[test]
+fn long_menu_or_slider() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                { "NAME": "mode", "TYPE": "long", "VALUES": [0, 1], "LABELS": ["Off", "On"] },
+                { "NAME": "taps", "TYPE": "long", "DEFAULT": 4, "MIN": 1, "MAX": 16 },
+                { "NAME": "seed", "TYPE": "long", "DEFAULT": 4 }
+            ]
+        }"#,
+    );
+    let (mode, taps, seed) = (long(&inputs[0]), long(&inputs[1]), long(&inputs[2]));
+    assert!(mode.is_menu() && !mode.is_slider());
+    assert!(!taps.is_menu() && taps.is_slider());
+    assert!(!seed.is_menu() && !seed.is_slider());
+}
+
+#[test]
+fn default_fallbacks() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                { "NAME": "amount", "TYPE": "float" },
+                { "NAME": "level", "TYPE": "float", "DEFAULT": 0.5 },
+                { "NAME": "invert", "TYPE": "bool" },
+                { "NAME": "center", "TYPE": "point2D" },
+                { "NAME": "mode", "TYPE": "long", "VALUES": [2, 3] },
+                { "NAME": "taps", "TYPE": "long", "DEFAULT": 3, "VALUES": [2, 3] },
+                { "NAME": "seed", "TYPE": "long" }
+            ]
+        }"#,
+    );
+    assert_eq!(float(&inputs[0]).default_or(1.0), 1.0);
+    assert_eq!(float(&inputs[1]).default_or(1.0), 0.5);
+    match inputs[2].ty {
+        isf::InputType::Bool(ref b) => assert!(!b.default_or_false()),
+        ref ty => panic!("expected bool input, found {:?}", ty),
+    }
+    assert_eq!(point(&inputs[3]).default_or_zero(), isf::Point2d::new(0.0, 0.0));
+    assert_eq!(long(&inputs[4]).default_or_first(), Some(2));
+    assert_eq!(long(&inputs[5]).default_or_first(), Some(3));
+    assert_eq!(long(&inputs[6]).default_or_first(), None);
+}
+
+#[test]
+fn default_values() {
+    use isf::InputValue;
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "inputImage", "TYPE": "image" },
+            { "NAME": "flash", "TYPE": "event" },
+            { "NAME": "invert", "TYPE": "bool", "DEFAULT": true },
+            { "NAME": "mode", "TYPE": "long", "VALUES": [2, 3] },
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5 },
+            { "NAME": "center", "TYPE": "point2D" },
+            { "NAME": "tint", "TYPE": "color", "DEFAULT": [1, 0, 0] },
+            { "NAME": "fft", "TYPE": "audioFFT" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let values = isf.default_values();
+    let expected: Vec<(&str, InputValue)> = vec![
+        ("amount", InputValue::Float(0.5)),
+        ("center", InputValue::Point2d([0.0, 0.0])),
+        ("fft", InputValue::AudioFft),
+        ("flash", InputValue::Event(false)),
+        ("inputImage", InputValue::Image),
+        ("invert", InputValue::Bool(true)),
+        ("mode", InputValue::Long(2)),
+        ("tint", InputValue::Color([1.0, 0.0, 0.0, 1.0])),
+    ];
+    let actual: Vec<_> = values.iter().map(|(k, v)| (&k[..], *v)).collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn clamp_value() {
+    use isf::InputValue;
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                { "NAME": "amount", "TYPE": "float", "MIN": 0.0, "MAX": 1.0 },
+                { "NAME": "center", "TYPE": "point2D", "MIN": [0, -1], "MAX": [1, 1] },
+                { "NAME": "mode", "TYPE": "long", "VALUES": [0, 10, 20] },
+                { "NAME": "taps", "TYPE": "long", "MIN": 1 },
+                { "NAME": "tint", "TYPE": "color", "MAX": [1, 1, 1, 1] }
+            ]
+        }"#,
+    );
+    let clamp = |ix: usize, v| inputs[ix].clamp_value(v).unwrap();
+    assert_eq!(clamp(0, InputValue::Float(1.5)), InputValue::Float(1.0));
+    assert_eq!(clamp(0, InputValue::Float(-0.5)), InputValue::Float(0.0));
+    assert_eq!(clamp(0, InputValue::Float(0.25)), InputValue::Float(0.25));
+    assert_eq!(
+        clamp(1, InputValue::Point2d([2.0, -3.0])),
+        InputValue::Point2d([1.0, -1.0])
+    );
+    assert_eq!(
+        clamp(1, InputValue::Point2d([0.5, 0.5])),
+        InputValue::Point2d([0.5, 0.5])
+    );
+    assert_eq!(clamp(2, InputValue::Long(13)), InputValue::Long(10));
+    assert_eq!(clamp(2, InputValue::Long(15)), InputValue::Long(10));
+    assert_eq!(clamp(2, InputValue::Long(99)), InputValue::Long(20));
+    assert_eq!(clamp(3, InputValue::Long(-4)), InputValue::Long(1));
+    assert_eq!(clamp(3, InputValue::Long(400)), InputValue::Long(400));
+    let tint = InputValue::Color([2.0, 0.0, 0.0, 1.0]);
+    assert_eq!(clamp(4, tint), tint);
+
+    let err = inputs[0].clamp_value(InputValue::Long(1)).unwrap_err();
+    assert_eq!(err.input, "amount");
+    assert_eq!(err.value, InputValue::Long(1));
+}
+
+#[test]
+fn buffered_values() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                { "NAME": "a", "TYPE": "bool", "DEFAULT": 1 },
+                { "NAME": "b", "TYPE": "bool", "DEFAULT": 0.0 },
+                { "DEFAULT": 2, "NAME": "c", "TYPE": "long", "VALUES": [1, 2] },
+                { "NAME": "d", "TYPE": "audio", "MAX": null }
+            ]
+        }"#,
+    );
+    let bools: Vec<_> = inputs[..2]
+        .iter()
+        .map(|i| match i.ty {
+            isf::InputType::Bool(ref b) => b.default,
+            ref ty => panic!("unexpected input type: {:?}", ty),
+        })
+        .collect();
+    assert_eq!(bools, vec![Some(true), Some(false)]);
+    assert_eq!(long(&inputs[2]).default, Some(2));
+    match inputs[3].ty {
+        isf::InputType::Audio(ref a) => assert_eq!(a.num_samples, None),
+        ref ty => panic!("unexpected input type: {:?}", ty),
+    }
+    let json = r#"[{ "NAME": "e", "TYPE": "float", "DEFAULT": "x" }]"#;
+    assert!(serde_json::from_str::<Vec<isf::Input>>(json).is_err());
+}
+
+#[test]
+fn bool_string_defaults() {
+    let inputs = parse_inputs(
+        r#"{
+            "INPUTS": [
+                { "NAME": "a", "TYPE": "bool", "DEFAULT": "YES" },
+                { "NAME": "b", "TYPE": "bool", "DEFAULT": "no" },
+                { "NAME": "c", "TYPE": "bool", "DEFAULT": "TRUE" }
+            ]
+        }"#,
+    );
+    let defaults: Vec<_> = inputs
+        .iter()
+        .map(|i| match i.ty {
+            isf::InputType::Bool(ref b) => b.default,
+            ref ty => panic!("unexpected input type: {:?}", ty),
+        })
+        .collect();
+    assert_eq!(defaults, vec![Some(true), Some(false), Some(true)]);
+    let json = r#"/*{ "INPUTS": [{ "NAME": "d", "TYPE": "bool", "DEFAULT": "on" }] }*/"#;
+    let err = isf::parse(json).unwrap_err();
+    assert!(err.to_string().contains("invalid boolean \"on\""), "{}", err);
+}
+
+#[test]
+fn input_type_from_values() {
+    use isf::{InputType, InputTypeKind};
+    let types: Vec<InputType> = vec![
+        isf::InputBool { default: Some(true) }.into(),
+        InputTypeKind::Long.default_type(),
+        isf::InputFloat::default().with_default(0.5).into(),
+        isf::InputPoint2d {
+            input_values: Default::default(),
+            coordinate_space: Default::default(),
+        }
+        .with_default([0.5, 0.5])
+        .into(),
+        isf::InputColor::default().with_default([1.0, 0.0, 0.0]).into(),
+        isf::InputAudio { num_samples: Some(256) }.into(),
+        isf::InputAudioFft { num_columns: None }.into(),
+    ];
+    let kinds: Vec<_> = types.iter().map(InputType::kind).collect();
+    use InputTypeKind::*;
+    assert_eq!(kinds, vec![Bool, Long, Float, Point2d, Color, Audio, AudioFft]);
+    let long = isf::InputLong {
+        input_values: Default::default(),
+        values: vec![0, 1],
+        labels: vec![],
+    };
+    assert_eq!(InputType::from(long.clone()), InputType::Long(long));
+
+    let input = isf::Input::builder("x", InputType::Image)
+        .ty(isf::InputFloat::default().with_range(0.0, 2.0))
+        .build();
+    assert_eq!(input.numeric_bounds(), Some((0.0, 2.0)));
+}
+
+#[test]
+fn normalize_float() {
+    let f = isf::InputFloat::default().with_range(-1.0, 3.0);
+    assert_eq!(f.normalize(-1.0), Some(0.0));
+    assert_eq!(f.normalize(1.0), Some(0.5));
+    assert_eq!(f.normalize(3.0), Some(1.0));
+    assert_eq!(f.normalize(5.0), Some(1.5));
+    assert_eq!(f.denormalize(0.25), Some(0.0));
+    assert_eq!(f.denormalize(f.normalize(2.0).unwrap()), Some(2.0));
+
+    // A reversed range maps the `MIN` to `0.0` all the same.
+    let reversed = isf::InputFloat::default().with_range(1.0, 0.0);
+    assert_eq!(reversed.normalize(1.0), Some(0.0));
+    assert_eq!(reversed.normalize(0.25), Some(0.75));
+    assert_eq!(reversed.denormalize(1.0), Some(0.0));
+
+    let empty = isf::InputFloat::default().with_range(2.0, 2.0);
+    assert_eq!(empty.normalize(2.0), Some(0.0));
+    assert_eq!(empty.denormalize(0.5), Some(2.0));
+
+    let unbounded = isf::InputFloat {
+        max: None,
+        ..f
+    };
+    assert_eq!(unbounded.normalize(0.5), None);
+    assert_eq!(unbounded.denormalize(0.5), None);
+}
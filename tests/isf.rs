@@ -0,0 +1,422 @@
+#![cfg(feature = "serde")]
+
+#[test]
+fn title_and_credit_roundtrip() {
+    let glsl_str = r#"/*{
+        "LABEL": "Soft Glow",
+        "CREDIT": "by VIDVOX",
+        "DESCRIPTION": "Makes things glow"
+    }*/"#;
+    let isf = isf::parse(glsl_str).unwrap();
+    assert_eq!(isf.title(), Some("Soft Glow"));
+    assert_eq!(isf.credit.as_deref(), Some("by VIDVOX"));
+    let json = serde_json::to_string(&isf).unwrap();
+    assert!(json.contains(r#""LABEL":"Soft Glow""#));
+    assert!(json.contains(r#""CREDIT":"by VIDVOX""#));
+    let isf2: isf::Isf = serde_json::from_str(&json).unwrap();
+    assert_eq!(isf, isf2);
+}
+
+#[test]
+fn title_absent() {
+    let isf = isf::parse("/*{}*/").unwrap();
+    assert_eq!(isf.title(), None);
+    let json = serde_json::to_string(&isf).unwrap();
+    assert!(!json.contains("LABEL"));
+    assert!(!json.contains("CREDIT"));
+}
+
+#[test]
+fn interface_diff() {
+    let a = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5 },
+            { "NAME": "mode", "TYPE": "long", "VALUES": [0, 1], "LABELS": ["A", "B"] },
+            { "NAME": "center", "TYPE": "point2D" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+
+    // Only values and ranges changed.
+    let b = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 1.0, "MIN": 0.0, "MAX": 2.0 },
+            { "NAME": "mode", "TYPE": "long", "VALUES": [0, 1, 2], "LABELS": ["A", "B", "C"] },
+            { "NAME": "center", "TYPE": "point2D", "LABEL": "Center" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    assert!(a.interface_diff(&b).is_unchanged());
+
+    let c = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "long" },
+            { "NAME": "center", "TYPE": "point2D" },
+            { "NAME": "tint", "TYPE": "color" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let diff = a.interface_diff(&c);
+    assert!(!diff.is_unchanged());
+    assert_eq!(diff.added, vec!["tint".to_string()]);
+    assert_eq!(diff.removed, vec!["mode".to_string()]);
+    assert_eq!(diff.retyped, vec!["amount".to_string()]);
+}
+
+#[test]
+fn match_categories() {
+    let isf =
+        isf::parse(r#"/*{ "CATEGORIES": ["Blur", " Stylize ", "color adjustment"] }*/"#).unwrap();
+    assert!(isf.matches_any_category(&["blur"]));
+    assert!(isf.matches_any_category(&["Distortion", "STYLIZE"]));
+    assert!(!isf.matches_any_category(&["Distortion", "Generator"]));
+    assert!(!isf.matches_any_category(&[]));
+    assert!(isf.matches_all_category(&["blur", "Color Adjustment "]));
+    assert!(!isf.matches_all_category(&["blur", "Distortion"]));
+    assert!(isf.matches_all_category(&[]));
+}
+
+#[test]
+fn upgrade_v1_persistent_buffers() {
+    let glsl_str = r#"/*{
+        "PERSISTENT_BUFFERS": ["accum", "unused"],
+        "PASSES": [{ "TARGET": "accum" }, {}]
+    }*/"#;
+    let mut isf = isf::parse(glsl_str).unwrap();
+    assert_eq!(isf.persistent_buffers.len(), 2);
+    let report = isf.upgrade_to_v2();
+    assert!(report.upgraded);
+    assert_eq!(report.previous_version, None);
+    assert_eq!(report.persistent_passes, vec![0]);
+    assert_eq!(
+        report.unmatched_persistent_buffers,
+        vec!["unused".to_string()]
+    );
+    assert_eq!(isf.isfvsn.as_deref(), Some("2.0"));
+    assert!(isf.passes[0].persistent);
+    assert!(!isf.passes[1].persistent);
+
+    // Upgrading again changes nothing.
+    let upgraded = isf.clone();
+    assert!(isf.upgrade_to_v2().is_empty());
+    assert_eq!(isf, upgraded);
+}
+
+#[test]
+fn upgrade_v1_persistent_buffer_dict() {
+    let glsl_str = r#"/*{
+        "ISFVSN": "1.0",
+        "PERSISTENT_BUFFERS": { "accum": { "WIDTH": "$WIDTH/2", "FLOAT": true } },
+        "PASSES": [{ "TARGET": "accum", "HEIGHT": 64 }, {}]
+    }*/"#;
+    let mut isf = isf::parse(glsl_str).unwrap();
+
+    // The dict form must survive a roundtrip.
+    let json = serde_json::to_string(&isf).unwrap();
+    assert_eq!(isf, serde_json::from_str(&json).unwrap());
+
+    let report = isf.upgrade_to_v2();
+    assert_eq!(report.previous_version.as_deref(), Some("1.0"));
+    let pass = &isf.passes[0];
+    assert!(pass.persistent && pass.float);
+    assert_eq!(pass.width.as_deref(), Some("$WIDTH/2"));
+    assert_eq!(pass.height.as_deref(), Some("64"));
+    assert!(isf.persistent_buffers.is_empty());
+    let json = serde_json::to_string(&isf).unwrap();
+    assert!(!json.contains("PERSISTENT_BUFFERS"));
+}
+
+#[test]
+fn upgrade_v2_unchanged() {
+    let glsl_str = r#"/*{ "ISFVSN": "2", "PASSES": [{ "TARGET": "a" }] }*/"#;
+    let mut isf = isf::parse(glsl_str).unwrap();
+    let original = isf.clone();
+    assert!(isf.upgrade_to_v2().is_empty());
+    assert_eq!(isf, original);
+}
+
+#[test]
+fn inputs_without_default() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "a", "TYPE": "float", "DEFAULT": 0.5 },
+            { "NAME": "b", "TYPE": "float" },
+            { "NAME": "c", "TYPE": "bool" },
+            { "NAME": "d", "TYPE": "long", "DEFAULT": 1 },
+            { "NAME": "e", "TYPE": "point2D" },
+            { "NAME": "f", "TYPE": "color" },
+            { "NAME": "g", "TYPE": "image" },
+            { "NAME": "h", "TYPE": "event" },
+            { "NAME": "i", "TYPE": "audio" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let names: Vec<_> = isf
+        .inputs_without_default()
+        .iter()
+        .map(|i| &i.name[..])
+        .collect();
+    assert_eq!(names, vec!["b", "c", "e", "f"]);
+}
+
+#[test]
+fn validate_imports() {
+    let isf = isf::parse(
+        r#"/*{
+        "IMPORTED": {
+            "lut": { "PATH": "lut.PNG" },
+            "noise": { "PATH": "noise.tiff" },
+            "raw": { "PATH": "textures/raw" }
+        }
+    }*/"#,
+    )
+    .unwrap();
+    let issues = isf.validate_imports(&["png", ".jpg"]);
+    assert_eq!(
+        issues,
+        vec![
+            isf::ImportIssue::UnsupportedExtension {
+                name: "noise".to_string(),
+                path: "noise.tiff".into(),
+                ext: "tiff".to_string(),
+            },
+            isf::ImportIssue::MissingExtension {
+                name: "raw".to_string(),
+                path: "textures/raw".into(),
+            },
+        ]
+    );
+    assert!(isf.validate_imports(&["png", "tiff", ""]).len() == 1);
+}
+
+#[test]
+fn to_markdown() {
+    let isf = isf::parse(
+        r#"/*{
+        "LABEL": "Soft Glow",
+        "DESCRIPTION": "Makes things glow",
+        "CATEGORIES": ["Glow", "Stylize"],
+        "INPUTS": [
+            { "NAME": "inputImage", "TYPE": "image" },
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5, "MIN": 0.0, "MAX": 2.0 },
+            {
+                "NAME": "mode",
+                "TYPE": "long",
+                "DEFAULT": 1,
+                "VALUES": [0, 1],
+                "LABELS": ["Soft", "Hard|Sharp"],
+                "LABEL": "Mode"
+            },
+            { "NAME": "center", "TYPE": "point2D", "DEFAULT": [0.5, 0.5] }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let expected = "\
+# Soft Glow
+
+Makes things glow
+
+**Categories:** Glow, Stylize
+
+| Name | Type | Range | Default | Label |
+| --- | --- | --- | --- | --- |
+| `inputImage` | image | - | - | - |
+| `amount` | float | 0 to 2 | 0.5 | - |
+| `mode` | long | 0 (Soft), 1 (Hard\\|Sharp) | 1 | Mode |
+| `center` | point2D | - | [0.5, 0.5] | - |
+";
+    assert_eq!(isf.to_markdown(), expected);
+    let empty = isf::parse("/*{}*/").unwrap();
+    assert_eq!(empty.to_markdown(), "This shader has no inputs.\n");
+}
+
+#[test]
+fn carryover_map() {
+    use isf::Carryover;
+    let old = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5, "MIN": 0.0, "MAX": 1.0 },
+            { "NAME": "mode", "TYPE": "long", "VALUES": [0, 1], "LABELS": ["A", "B"] },
+            { "NAME": "center", "TYPE": "point2D" },
+            { "NAME": "invert", "TYPE": "bool" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let new = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 1.0, "MIN": 0.0, "MAX": 1.0 },
+            { "NAME": "mode", "TYPE": "long", "VALUES": [0, 1, 2], "LABELS": ["A", "B", "C"] },
+            { "NAME": "center", "TYPE": "color" },
+            { "NAME": "tint", "TYPE": "color" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let map = old.carryover_map(&new);
+    let expected = vec![
+        ("amount", Carryover::Unchanged),
+        ("center", Carryover::Retyped),
+        ("invert", Carryover::Removed),
+        ("mode", Carryover::RangeChanged),
+        ("tint", Carryover::Added),
+    ];
+    let map: Vec<_> = map.iter().map(|(k, &v)| (&k[..], v)).collect();
+    assert_eq!(map, expected);
+    assert!(old.inputs[0].same_signature(&new.inputs[0]));
+    assert!(!old.inputs[2].same_signature(&new.inputs[2]));
+}
+
+#[test]
+fn description_or() {
+    let isf = isf::parse(r#"/*{ "DESCRIPTION": "Makes things glow" }*/"#).unwrap();
+    assert_eq!(isf.description_or("Glow.fs"), "Makes things glow");
+    let isf = isf::parse(r#"/*{ "DESCRIPTION": "  " }*/"#).unwrap();
+    assert_eq!(isf.description_or("Glow.fs"), "Glow.fs");
+    let isf = isf::parse("/*{}*/").unwrap();
+    assert_eq!(isf.description_or("Glow.fs"), "Glow.fs");
+}
+
+#[test]
+fn minimize_version() {
+    use isf::IsfVersion;
+    let mut isf = isf::parse(
+        r#"/*{
+        "ISFVSN": "2.0",
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float" },
+            { "NAME": "fft", "TYPE": "audioFFT" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(isf.detected_version(), IsfVersion::V2);
+    isf.minimize_version();
+    assert_eq!(isf.isfvsn.as_deref(), Some("2.0"));
+
+    isf.inputs.pop();
+    assert_eq!(isf.detected_version(), IsfVersion::V1);
+    isf.minimize_version();
+    assert_eq!(isf.isfvsn.as_deref(), Some("1.0"));
+
+    // The version is never raised.
+    isf.passes.push(isf::Pass::builder().target("feedback").persistent(true).build());
+    isf.minimize_version();
+    assert_eq!(isf.isfvsn.as_deref(), Some("1.0"));
+
+    assert_eq!(
+        IsfVersion::parse("2"),
+        Some(IsfVersion { major: 2, minor: 0 })
+    );
+    assert_eq!(IsfVersion::parse("two"), None);
+}
+
+#[test]
+fn isf_builder() {
+    let built = isf::Isf::builder()
+        .isfvsn("2")
+        .description("Tints the input image.")
+        .category("Color")
+        .input(isf::Input::builder("inputImage", isf::InputType::Image))
+        .input(
+            isf::Input::builder("amount", isf::InputType::Float(Default::default()))
+                .label("Amount")
+                .help("How much to tint."),
+        )
+        .pass(isf::Pass::to_screen())
+        .import("noise", "noise.png")
+        .build();
+    let parsed = isf::parse(
+        r#"/*{
+        "ISFVSN": "2",
+        "DESCRIPTION": "Tints the input image.",
+        "CATEGORIES": ["Color"],
+        "INPUTS": [
+            { "NAME": "inputImage", "TYPE": "image" },
+            { "NAME": "amount", "TYPE": "float", "LABEL": "Amount", "HELP": "How much to tint." }
+        ],
+        "PASSES": [{}],
+        "IMPORTED": { "noise": { "PATH": "noise.png" } }
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(built, parsed);
+}
+
+#[test]
+fn input_lookup() {
+    let mut isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "LABEL": "First" },
+            { "NAME": "amount", "TYPE": "float", "LABEL": "Second" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(isf.input("amount").unwrap().label.as_deref(), Some("First"));
+    assert!(isf.input("missing").is_none());
+    isf.input_mut("amount").unwrap().label = None;
+    assert_eq!(isf.inputs[0].label, None);
+    assert_eq!(
+        isf.validate(),
+        Err(vec![isf::ValidationError::DuplicateInput {
+            input: "amount".to_string(),
+        }])
+    );
+}
+
+#[test]
+fn isf_version() {
+    use isf::IsfVersion;
+    let version = |vsn: Option<&str>| {
+        let mut builder = isf::Isf::builder();
+        if let Some(vsn) = vsn {
+            builder = builder.isfvsn(vsn);
+        }
+        builder.build().version()
+    };
+    assert_eq!(version(Some("2")), Some(IsfVersion::V2));
+    assert_eq!(version(Some("2.0")), Some(IsfVersion::V2));
+    assert_eq!(version(Some("1.0")), Some(IsfVersion::V1));
+    assert_eq!(
+        version(Some("2.1")),
+        Some(IsfVersion { major: 2, minor: 1 })
+    );
+    assert_eq!(version(Some("v2")), None);
+    assert_eq!(version(None), None);
+    assert!(version(Some("1.0")) < version(Some("2")));
+}
+
+#[test]
+fn isf_default() {
+    let mut isf = isf::Isf::default();
+    assert_eq!(isf, isf::Isf::builder().build());
+    assert_eq!(isf, isf::parse("/*{}*/").unwrap());
+    isf.passes.push(isf::Pass::to_screen());
+    assert_eq!(isf.passes.len(), 1);
+}
+
+#[test]
+fn resolve_imports() {
+    let base_dir = std::env::temp_dir().join("shaders");
+    let absolute = std::env::temp_dir().join("noise.png");
+    let mut isf = isf::Isf::builder()
+        .import("lut", "textures/lut.png")
+        .import("noise", &absolute)
+        .build();
+    isf.resolve_imports(&base_dir);
+    assert_eq!(isf.imported["lut"].path, base_dir.join("textures/lut.png"));
+    assert_eq!(isf.imported["noise"].path, absolute);
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "serde")]
+
+use isf::{LintOptions, Warning};
+
+#[test]
+fn label_warnings() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "LABEL": "Amount" },
+            { "NAME": "mode", "TYPE": "long", "LABEL": "Blend\nMode" },
+            { "NAME": "center", "TYPE": "point2D", "LABEL": "The centre of the radial blur" },
+            { "NAME": "tint", "TYPE": "color" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let report = isf.lint(&LintOptions::default());
+    assert_eq!(
+        report.warnings,
+        vec![Warning::LabelControlChars {
+            input: "mode".to_string()
+        }]
+    );
+
+    let options = LintOptions { max_label_len: 10 };
+    let report = isf.lint(&options);
+    assert_eq!(
+        report.warnings,
+        vec![
+            Warning::LabelControlChars {
+                input: "mode".to_string()
+            },
+            Warning::LabelTooLong {
+                input: "center".to_string(),
+                len: 29,
+                max: 10,
+            },
+        ]
+    );
+    assert_eq!(
+        report.warnings[1].to_string(),
+        "the label of input `center` is 29 characters long, exceeding the maximum of 10"
+    );
+}
+
+#[test]
+fn lint_clean() {
+    let isf = isf::parse(r#"/*{ "INPUTS": [{ "NAME": "a", "TYPE": "bool", "LABEL": "A" }] }*/"#);
+    assert!(isf.unwrap().lint(&LintOptions::default()).is_empty());
+}
+
+#[test]
+fn parse_verbose_precision_loss() {
+    let (isf, warnings) = isf::parse_verbose(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.123456789, "MIN": 0.25, "MAX": 1 },
+            { "NAME": "center", "TYPE": "point2D", "DEFAULT": [0.5, 0.333333333] },
+            { "NAME": "count", "TYPE": "long", "DEFAULT": 3 }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(isf.inputs.len(), 3);
+    assert_eq!(
+        warnings,
+        vec![
+            Warning::PrecisionLoss {
+                input: "amount".to_string(),
+                field: "DEFAULT".to_string(),
+                literal: 0.123456789,
+                stored: 0.12345679,
+            },
+            Warning::PrecisionLoss {
+                input: "center".to_string(),
+                field: "DEFAULT".to_string(),
+                literal: 0.333333333,
+                stored: 0.33333334,
+            },
+        ]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "the DEFAULT of input `amount` is written as 0.123456789 but stored as 0.12345679104328156"
+    );
+
+    // Even short literals are reported when they are written differently upon serialization.
+    let src = r#"/*{ "INPUTS": [{ "NAME": "amount", "TYPE": "float", "DEFAULT": 0.1 }] }*/"#;
+    let (isf, warnings) = isf::parse_verbose(src).unwrap();
+    assert_eq!(
+        warnings,
+        vec![Warning::PrecisionLoss {
+            input: "amount".to_string(),
+            field: "DEFAULT".to_string(),
+            literal: 0.1,
+            stored: 0.1,
+        }]
+    );
+    let json = serde_json::to_string(&isf).unwrap();
+    assert!(json.contains(&f64::from(0.1f32).to_string()), "{}", json);
+    assert_eq!(
+        warnings[0].to_string(),
+        "the DEFAULT of input `amount` is written as 0.1 but stored as 0.10000000149011612"
+    );
+}
@@ -0,0 +1,20 @@
+// The data model must remain usable when built without the `serde` feature.
+
+use isf::{Input, InputFloat, InputType, Isf, Pass};
+
+#[test]
+fn build_and_validate() {
+    let isf = Isf::builder()
+        .input(Input::builder("inputImage", InputType::Image))
+        .input(Input::builder(
+            "amount",
+            InputType::Float(InputFloat::default().with_default(0.5).with_range(0.0, 1.0)),
+        ))
+        .pass(Pass::builder().target("half").dimensions("$WIDTH / 2", "$HEIGHT / 2"))
+        .pass(Pass::to_screen())
+        .build();
+    assert_eq!(isf.validate(), Ok(()));
+    assert_eq!(isf.input("amount").unwrap().numeric_default(), Some(0.5));
+    assert!(isf.uniform_declarations().contains("uniform float amount;"));
+    assert!(isf::contains_isf("/*{ \"INPUTS\": [] }*/"));
+}
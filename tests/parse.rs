@@ -1,3 +1,5 @@
+#![cfg(feature = "serde")]
+
 // Check that we can parse and deserialize every test file.
 #[test]
 fn parse_test_files() {
@@ -19,3 +21,329 @@ fn parse_test_files() {
         }
     }
 }
+
+#[test]
+fn parse_lenient_decimal_commas() {
+    let glsl_str = r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 0,5, "MIN": -1,25, "MAX": 2 },
+            { "NAME": "center", "TYPE": "point2D", "DEFAULT": [0,5], "MAX": [1,2] },
+            { "NAME": "mode", "TYPE": "long", "VALUES": [0,1,2], "LABELS": ["0,5", "a", "b"] }
+        ]
+    }*/"#;
+    assert!(isf::parse(glsl_str).is_err());
+    let isf = isf::parse_lenient(glsl_str).unwrap();
+    match isf.inputs[0].ty {
+        isf::InputType::Float(ref f) => {
+            assert_eq!(f.default, Some(0.5));
+            assert_eq!(f.min, Some(-1.25));
+            assert_eq!(f.max, Some(2.0));
+        }
+        ref ty => panic!("unexpected input type: {:?}", ty),
+    }
+    // Arrays are already valid JSON and must not be modified.
+    match isf.inputs[1].ty {
+        isf::InputType::Point2d(ref p) => {
+            assert_eq!(p.default, Some(isf::Point2d::new(0.0, 5.0)));
+            assert_eq!(p.max, Some(isf::Point2d::new(1.0, 2.0)));
+        }
+        ref ty => panic!("unexpected input type: {:?}", ty),
+    }
+    match isf.inputs[2].ty {
+        isf::InputType::Long(ref l) => {
+            assert_eq!(l.values, vec![0, 1, 2]);
+            assert_eq!(l.labels[0], "0,5");
+        }
+        ref ty => panic!("unexpected input type: {:?}", ty),
+    }
+}
+
+#[test]
+fn parse_lenient_valid_json_unchanged() {
+    let glsl_str =
+        r#"/*{ "INPUTS": [{ "NAME": "a", "TYPE": "float", "DEFAULT": 10, "MAX": 1e3 }] }*/"#;
+    assert_eq!(isf::parse(glsl_str).unwrap(), isf::parse_lenient(glsl_str).unwrap());
+}
+
+#[test]
+fn parse_file() {
+    let test_files_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files");
+    let isf = isf::parse_file(test_files_path.join("Test-Bool.fs")).unwrap();
+    assert!(!isf.inputs.is_empty());
+
+    let missing = test_files_path.join("Does Not Exist.fs");
+    match isf::parse_file(&missing) {
+        Err(err @ isf::ParseError::Io { .. }) => {
+            assert!(err.to_string().contains("Does Not Exist.fs"));
+        }
+        res => panic!("expected an IO error, found {:?}", res),
+    }
+
+    // Files that exist but contain no ISF blob are not IO errors.
+    match isf::parse_file(test_files_path.join("Rotate.vs")) {
+        Err(isf::ParseError::MissingTopComment) => (),
+        res => panic!("expected a missing top comment error, found {:?}", res),
+    }
+}
+
+#[test]
+fn parse_with_source() {
+    let glsl_str =
+        "// License header.\n/*{\n    \"INPUTS\": []\n}*/\n\nvoid main() {\n    error;\n}\n";
+    let (isf, body) = isf::parse_with_source(glsl_str).unwrap();
+    assert_eq!(isf, isf::parse(glsl_str).unwrap());
+    assert_eq!(body.src, "\n\nvoid main() {\n    error;\n}\n");
+    assert_eq!(&glsl_str[body.offset..], body.src);
+    assert_eq!(body.line, 4);
+    // `error;` is on line 4 of the body and line 7 of the original source.
+    assert_eq!(body.src.lines().nth(3), Some("    error;"));
+    assert_eq!(body.source_line(4), 7);
+    assert_eq!(glsl_str.lines().nth(6), Some("    error;"));
+}
+
+#[test]
+fn contains_isf() {
+    assert!(isf::contains_isf("/*{\n\t\"INPUTS\": []\n}*/\nvoid main() {}"));
+    assert!(isf::contains_isf("\n/* {} */"));
+    assert!(!isf::contains_isf("/* Copyright 2020 */\nvoid main() {}"));
+    assert!(!isf::contains_isf("// {\"INPUTS\": []}\nvoid main() {}"));
+    assert!(!isf::contains_isf("/*{ unterminated"));
+    let test_files_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files");
+    for entry in std::fs::read_dir(test_files_path).unwrap() {
+        let path = entry.unwrap().path();
+        let ext = path.extension().and_then(|s| s.to_str());
+        if ext == Some("fs") || ext == Some("vs") {
+            let glsl_str = std::fs::read_to_string(&path).unwrap();
+            let parsed = isf::parse(&glsl_str).is_ok();
+            assert_eq!(isf::contains_isf(&glsl_str), parsed, "{}", path.display());
+        }
+    }
+}
+
+#[test]
+fn parse_html_embedded() {
+    let html = r#"<html>
+<head>
+<!--{
+    "DESCRIPTION": "Inverts the image",
+    "INPUTS": [{ "NAME": "inputImage", "TYPE": "image" }]
+}-->
+<script type="x-shader/x-fragment">
+void main() { gl_FragColor = vec4(1.0) - IMG_THIS_PIXEL(inputImage); }
+</script>
+</head>
+</html>"#;
+    let isf = isf::parse_html_embedded(html).unwrap();
+    assert_eq!(isf.description.as_deref(), Some("Inverts the image"));
+    assert_eq!(isf.inputs[0].name, "inputImage");
+    match isf::parse_html_embedded("/*{}*/") {
+        Err(isf::ParseError::MissingTopComment) => (),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    let options = isf::ExtractOptions {
+        open: "{#".to_string(),
+        close: "#}".to_string(),
+    };
+    let isf = isf::parse_with_options(r#"{# { "VSN": "1.0" } #}"#, &options).unwrap();
+    assert_eq!(isf.vsn.as_deref(), Some("1.0"));
+}
+
+#[test]
+fn parse_with_options_matches_parse() {
+    // A leading license comment and a closing delimiter within a JSON string.
+    let glsl_str = r#"/* Copyright (c) Example. */
+/*{ "DESCRIPTION": "Closes */ early", "INPUTS": [] }*/
+void main() {}"#;
+    let options = isf::ExtractOptions::default();
+    let isf = isf::parse_with_options(glsl_str, &options).unwrap();
+    assert_eq!(isf, isf::parse(glsl_str).unwrap());
+    assert_eq!(isf.description.as_deref(), Some("Closes */ early"));
+
+    let html = r#"<!-- Generated by an editor -->
+<!--{ "DESCRIPTION": "Closes --> early" }-->"#;
+    let isf = isf::parse_html_embedded(html).unwrap();
+    assert_eq!(isf.description.as_deref(), Some("Closes --> early"));
+}
+
+#[test]
+fn parse_doc_comment_markers() {
+    let glsl_str = r#"/*!{
+    "DESCRIPTION": "Documented",
+    "INPUTS": [{ "NAME": "amount", "TYPE": "float" }]
+}!*/
+void main() {}
+"#;
+    let isf = isf::parse(glsl_str).unwrap();
+    assert_eq!(isf.description.as_deref(), Some("Documented"));
+    assert!(isf::contains_isf(glsl_str));
+    let (_, body) = isf::parse_with_source(glsl_str).unwrap();
+    assert_eq!(body.src, "\nvoid main() {}\n");
+    // Only the leading marker.
+    assert!(isf::parse("/*! {} */").is_ok());
+}
+
+#[test]
+fn parse_with_transform() {
+    let glsl_str = r#"/*{
+    "INPUTS": [
+        { "NAME": "Amount", "TYPE": "float", "DEFAULT": 4.0, "MAX": 2.0 },
+        { "NAME": "inputImage", "TYPE": "image" }
+    ]
+}*/"#;
+    let mut seen = vec![];
+    let isf = isf::parse_with_transform(glsl_str, |mut input| {
+        seen.push(input.name.clone());
+        input.name = input.name.to_lowercase();
+        if let isf::InputType::Float(ref mut f) = input.ty {
+            if let (Some(default), Some(max)) = (f.default, f.max) {
+                f.default = Some(default.min(max));
+            }
+        }
+        input
+    })
+    .unwrap();
+    assert_eq!(seen, vec!["Amount", "inputImage"]);
+    assert_eq!(isf.inputs[0].name, "amount");
+    assert_eq!(isf.inputs[0].numeric_default(), Some(2.0));
+    assert_eq!(isf.inputs[1].name, "inputimage");
+}
+
+#[test]
+fn json_error_position() {
+    let glsl_str = r#"// Header
+/*{
+    "INPUTS": [
+        { "NAME": "amount", "TYPE": "float" }
+    ],
+    "PASSES": [,]
+}*/
+void main() {}
+"#;
+    match isf::parse(glsl_str) {
+        Err(isf::ParseError::Json { line, column, .. }) => assert_eq!((line, column), (6, 16)),
+        res => panic!("expected a JSON error, found {:?}", res),
+    }
+    // Errors on the first line of the JSON are offset by the comment's column.
+    let glsl_str = "  /* { \"VSN\": } */";
+    let err = isf::parse(glsl_str).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("failed to parse JSON at line 1, column 15 of source"),
+        "{}",
+        err
+    );
+    match isf::parse_lenient(glsl_str) {
+        Err(isf::ParseError::Json { line, column, .. }) => assert_eq!((line, column), (1, 15)),
+        res => panic!("expected a JSON error, found {:?}", res),
+    }
+}
+
+#[test]
+fn parse_after_leading_directives() {
+    let glsl_str = "#version 150\n// Copyright /* 2020 */\n/* Licensed under MIT. */\n\
+                    /*{ \"DESCRIPTION\": \"Second\" }*/\nvoid main() {}";
+    let isf = isf::parse(glsl_str).unwrap();
+    assert_eq!(isf.description.as_deref(), Some("Second"));
+    let (_, body) = isf::parse_with_source(glsl_str).unwrap();
+    assert_eq!(body.src, "\nvoid main() {}");
+    match isf::parse("#version 150\n/* Not JSON. */\nvoid main() {}") {
+        Err(isf::ParseError::MissingTopComment) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_comment_close_within_string() {
+    let glsl_str = r#"/*{
+    "DESCRIPTION": "Strips /* comments */ and \"*/\" quotes",
+    "INPUTS": []
+}*/
+void main() {}"#;
+    let isf = isf::parse(glsl_str).unwrap();
+    assert_eq!(
+        isf.description.as_deref(),
+        Some("Strips /* comments */ and \"*/\" quotes")
+    );
+    let (_, body) = isf::parse_with_source(glsl_str).unwrap();
+    assert_eq!(body.src, "\nvoid main() {}");
+    // An unterminated string is still reported as a JSON error.
+    match isf::parse("/*{ \"DESCRIPTION\": \"oops }*/\nvoid main() {}") {
+        Err(isf::ParseError::Json { .. }) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn find_comment() {
+    let json = r#"{ "DESCRIPTION": "Contains */ within a string", "INPUTS": [] }"#;
+    let glsl_str = format!("#version 150\n/* License. */\n/*{}*/\nvoid main() {{}}", json);
+    let range = isf::find_comment(&glsl_str).unwrap();
+    assert_eq!(&glsl_str[range.clone()], json);
+    // Replacing the range leaves the rest of the source untouched.
+    let mut edited = glsl_str.clone();
+    edited.replace_range(range, r#"{ "INPUTS": [] }"#);
+    assert_eq!(
+        edited,
+        "#version 150\n/* License. */\n/*{ \"INPUTS\": [] }*/\nvoid main() {}"
+    );
+    // Whitespace and `!` markers.
+    let range = isf::find_comment("/*!\n{}\n!*/").unwrap();
+    assert_eq!(range, 3..7);
+    assert_eq!(isf::find_comment("/* Not JSON. */"), None);
+}
+
+#[test]
+fn parse_with_body() {
+    let glsl_str = "/*{ \"INPUTS\": [] }*/\nvoid main() {}\n";
+    let (isf, body) = isf::parse_with_body(glsl_str).unwrap();
+    assert_eq!(isf, isf::parse(glsl_str).unwrap());
+    assert_eq!(body, "\nvoid main() {}\n");
+    assert_eq!(body, isf::parse_with_source(glsl_str).unwrap().1.src);
+    match isf::parse_with_body("void main() {}") {
+        Err(isf::ParseError::MissingTopComment) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_imported_array() {
+    let test_files_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files");
+    let array = isf::parse_file(test_files_path.join("Test-ImportedImageArray.fs")).unwrap();
+    let dict = isf::parse_file(test_files_path.join("Test-ImportedImage.fs")).unwrap();
+    assert_eq!(array.imported, dict.imported);
+    assert_eq!(array.imported.len(), 1);
+    let path = &array.imported["blendImage"].path;
+    assert_eq!(path, std::path::Path::new("Hexagon.tiff"));
+}
+
+#[test]
+fn parse_imported_errors() {
+    for imported in &[r#"{ "a": {} }"#, r#"[{ "NAME": "a" }]"#] {
+        let glsl_str = format!(r#"/*{{ "IMPORTED": {} }}*/"#, imported);
+        let msg = isf::parse(&glsl_str).unwrap_err().to_string();
+        assert!(msg.contains("missing field `PATH`"), "{}", msg);
+    }
+    let msg = isf::parse(r#"/*{ "IMPORTED": [{ "PATH": "a.png" }] }*/"#)
+        .unwrap_err()
+        .to_string();
+    assert!(msg.contains("missing field `NAME`"), "{}", msg);
+    let msg = isf::parse(r#"/*{ "IMPORTED": "a.png" }*/"#).unwrap_err().to_string();
+    assert!(msg.contains("a dict of imports or an array of named imports"), "{}", msg);
+}
+
+#[test]
+fn parse_mixed_case_types() {
+    let test_files_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files");
+    let isf = isf::parse_file(test_files_path.join("Test-MixedCaseTypes.fs")).unwrap();
+    let kinds: Vec<_> = isf.inputs.iter().map(|i| i.ty.kind()).collect();
+    use isf::InputTypeKind::*;
+    assert_eq!(kinds, vec![Image, Float, Point2d, AudioFft, Bool]);
+    assert_eq!(isf.inputs[1].numeric_default(), Some(0.5));
+    // Types are written back in the casing of the spec.
+    let json = serde_json::to_string(&isf).unwrap();
+    assert!(json.contains(r#""TYPE":"point2D""#) && json.contains(r#""TYPE":"audioFFT""#));
+    // Unknown types are still rejected.
+    let err = isf::parse(r#"/*{ "INPUTS": [{ "NAME": "a", "TYPE": "Vec3" }] }*/"#).unwrap_err();
+    assert!(err.to_string().contains("unknown ISF input type \"Vec3\""), "{}", err);
+}
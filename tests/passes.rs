@@ -0,0 +1,355 @@
+#![cfg(feature = "serde")]
+
+const BLUR: &str = r#"/*{
+    "PASSES": [
+        { "TARGET": "downA", "WIDTH": "$WIDTH/4.0", "HEIGHT": "$HEIGHT/4.0" },
+        { "TARGET": "downB", "WIDTH": "$WIDTH/4.0", "HEIGHT": "$HEIGHT/4.0" },
+        { "TARGET": "blurred", "WIDTH": "$WIDTH/4.0", "HEIGHT": "$HEIGHT/4.0" },
+        {}
+    ]
+}*/
+
+void main() {
+    if (PASSINDEX == 0) {
+        // Mentioning blurred in a comment must not count as a dependency.
+        gl_FragColor = IMG_THIS_PIXEL(inputImage);
+    } else if (PASSINDEX == 1) {
+        gl_FragColor = IMG_THIS_PIXEL(inputImage) * 0.5;
+    } else if (PASSINDEX == 2) {
+        gl_FragColor = IMG_THIS_PIXEL(downA) + IMG_THIS_PIXEL(downB);
+    } else {
+        gl_FragColor = IMG_THIS_PIXEL(blurred);
+    }
+}
+"#;
+
+fn parse_with_body(glsl_str: &str) -> (isf::Isf, &str) {
+    let (isf, body) = isf::parse_with_source(glsl_str).unwrap();
+    (isf, body.src)
+}
+
+#[test]
+fn pass_source_selects_branch() {
+    let (_, body) = parse_with_body(BLUR);
+    let pass2 = isf::glsl::pass_source(body, 2);
+    assert!(isf::glsl::references(&pass2, "downA"));
+    assert!(!isf::glsl::references(&pass2, "blurred"));
+    let pass3 = isf::glsl::pass_source(body, 3);
+    assert!(!isf::glsl::references(&pass3, "downA"));
+    assert!(isf::glsl::references(&pass3, "blurred"));
+    assert_eq!(pass3.len(), body.len());
+}
+
+#[test]
+fn pass_dependencies() {
+    let (isf, body) = parse_with_body(BLUR);
+    let deps = isf.pass_dependencies(body);
+    assert_eq!(deps, vec![vec![], vec![], vec![0, 1], vec![2]]);
+}
+
+#[test]
+fn fusable_passes() {
+    let (isf, body) = parse_with_body(BLUR);
+    // Passes 0 and 1 share a resolution and are independent. Pass 2 reads pass 1 and pass 3
+    // renders at a different resolution.
+    assert_eq!(isf.fusable_passes(body), vec![(0, 1)]);
+}
+
+#[test]
+fn execution_order() {
+    let (isf, body) = parse_with_body(BLUR);
+    assert_eq!(isf.execution_order(body).unwrap(), vec![0, 1, 2, 3]);
+
+    // A single implicit pass.
+    let (isf, body) = parse_with_body("/*{}*/ void main() {}");
+    assert_eq!(isf.execution_order(body).unwrap(), vec![0]);
+}
+
+#[test]
+fn execution_order_out_of_order() {
+    let glsl_str = r#"/*{
+        "PASSES": [{ "TARGET": "b" }, { "TARGET": "a" }, {}]
+    }*/
+    void main() {
+        if (PASSINDEX == 0) gl_FragColor = IMG_THIS_PIXEL(a);
+        else if (PASSINDEX == 1) gl_FragColor = vec4(1.0);
+        else gl_FragColor = IMG_THIS_PIXEL(b);
+    }
+    "#;
+    let (isf, body) = parse_with_body(glsl_str);
+    assert_eq!(isf.execution_order(body).unwrap(), vec![1, 0, 2]);
+}
+
+#[test]
+fn execution_order_cycle() {
+    let glsl_str = r#"/*{
+        "PASSES": [{ "TARGET": "a" }, { "TARGET": "b" }, {}]
+    }*/
+    void main() {
+        if (PASSINDEX == 0) gl_FragColor = IMG_THIS_PIXEL(b);
+        else if (PASSINDEX == 1) gl_FragColor = IMG_THIS_PIXEL(a);
+        else gl_FragColor = IMG_THIS_PIXEL(b);
+    }
+    "#;
+    let (isf, body) = parse_with_body(glsl_str);
+    let err = isf.execution_order(body).unwrap_err();
+    assert_eq!(err.passes, vec![0, 1, 2]);
+
+    // Reading a later persistent target reads the previous frame, so there's no cycle.
+    let persistent = glsl_str.replace(r#""TARGET": "b""#, r#""TARGET": "b", "PERSISTENT": true"#);
+    let (isf, body) = parse_with_body(&persistent);
+    assert_eq!(isf.execution_order(body).unwrap(), vec![0, 1, 2]);
+}
+
+#[test]
+fn check_passindex_usage() {
+    let (isf, body) = parse_with_body(BLUR);
+    assert_eq!(isf.check_passindex_usage(body), None);
+
+    let glsl_str = r#"/*{ "PASSES": [{ "TARGET": "a" }, {}] }*/
+    void main() {
+        // PASSINDEX is only mentioned in a comment.
+        gl_FragColor = IMG_THIS_PIXEL(a);
+    }
+    "#;
+    let (isf, body) = parse_with_body(glsl_str);
+    assert_eq!(
+        isf.check_passindex_usage(body),
+        Some(isf::Warning::PassIndexUnused { passes: 2 })
+    );
+
+    // Single pass shaders have no need to read `PASSINDEX`.
+    let (isf, body) = parse_with_body("/*{ \"PASSES\": [{}] }*/ void main() {}");
+    assert_eq!(isf.check_passindex_usage(body), None);
+}
+
+#[test]
+fn isolate_pass() {
+    let (isf, _) = parse_with_body(BLUR);
+    let isolated = isf.isolate_pass(2).unwrap();
+    assert_eq!(isolated.passes.len(), 1);
+    let pass = &isolated.passes[0];
+    assert_eq!(pass.target, None);
+    assert_eq!(pass.width, None);
+    assert_eq!(pass.height, None);
+    assert_eq!(isolated.inputs, isf.inputs);
+    assert_eq!(isolated.imported, isf.imported);
+    assert!(isf.isolate_pass(4).is_none());
+}
+
+#[test]
+fn parse_passes_only() {
+    let isf = isf::parse(BLUR).unwrap();
+    let passes: Vec<_> = isf::parse_passes_only(BLUR).map(Result::unwrap).collect();
+    assert_eq!(passes, isf.iter_passes().cloned().collect::<Vec<_>>());
+
+    // Passes are only deserialized as the iterator advances.
+    let glsl_str = r#"/*{ "PASSES": [{ "TARGET": "a" }, { "TARGET": 1 }] }*/"#;
+    let mut passes = isf::parse_passes_only(glsl_str);
+    assert_eq!(passes.next().unwrap().unwrap().target.as_deref(), Some("a"));
+    assert!(passes.next().unwrap().is_err());
+    assert!(passes.next().is_none());
+
+    let mut passes = isf::parse_passes_only("void main() {}");
+    assert!(matches!(
+        passes.next(),
+        Some(Err(isf::ParseError::MissingTopComment))
+    ));
+    assert!(passes.next().is_none());
+    assert_eq!(isf::parse_passes_only("/*{}*/").count(), 0);
+}
+
+#[test]
+fn pass_builder() {
+    let screen = isf::Pass::to_screen();
+    assert_eq!(serde_json::to_string(&screen).unwrap(), "{}");
+    let target = isf::Pass::to_target("down");
+    assert_eq!(
+        serde_json::to_string(&target).unwrap(),
+        r#"{"TARGET":"down"}"#
+    );
+
+    let pass = isf::Pass::builder()
+        .target("feedback")
+        .persistent(true)
+        .float(false)
+        .width_expr("$WIDTH/2")
+        .build();
+    let json = serde_json::to_string(&pass).unwrap();
+    assert_eq!(
+        json,
+        r#"{"TARGET":"feedback","PERSISTENT":true,"WIDTH":"$WIDTH/2"}"#
+    );
+    let parsed: isf::Pass = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, pass);
+}
+
+#[test]
+fn orphan_passes() {
+    let (isf, body) = parse_with_body(BLUR);
+    assert!(isf.orphan_passes(body).is_empty());
+
+    let glsl_str = r#"/*{
+    "PASSES": [
+        { "TARGET": "unused" },
+        { "TARGET": "trail", "PERSISTENT": true },
+        { "TARGET": "ghost", "PERSISTENT": true },
+        { "TARGET": "late" },
+        {}
+    ]
+}*/
+void main() {
+    if (PASSINDEX == 0) {
+        gl_FragColor = IMG_THIS_PIXEL(ghost) + IMG_THIS_PIXEL(late);
+    } else if (PASSINDEX == 1) {
+        gl_FragColor = IMG_THIS_PIXEL(trail) * 0.9;
+    } else {
+        gl_FragColor = IMG_THIS_PIXEL(inputImage);
+    }
+}
+"#;
+    let (isf, body) = parse_with_body(glsl_str);
+    assert_eq!(isf.orphan_passes(body), vec![0, 3]);
+}
+
+#[test]
+fn required_textures() {
+    use isf::{TextureKind, TextureRequirement};
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "inputImage", "TYPE": "image" },
+            { "NAME": "amount", "TYPE": "float" },
+            { "NAME": "wave", "TYPE": "audio" },
+            { "NAME": "fft", "TYPE": "audioFFT" }
+        ],
+        "IMPORTED": {
+            "noise": { "PATH": "noise.png" },
+            "lut": { "PATH": "lut.png" }
+        },
+        "PASSES": [
+            { "TARGET": "blurX" },
+            { "TARGET": "blurY" },
+            { "TARGET": "blurX" },
+            {}
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let texture = |kind, name: &str| TextureRequirement {
+        kind,
+        name: name.to_string(),
+    };
+    assert_eq!(
+        isf.required_textures(),
+        vec![
+            texture(TextureKind::Image, "inputImage"),
+            texture(TextureKind::Audio, "wave"),
+            texture(TextureKind::AudioFft, "fft"),
+            texture(TextureKind::Imported, "lut"),
+            texture(TextureKind::Imported, "noise"),
+            texture(TextureKind::Target, "blurX"),
+            texture(TextureKind::Target, "blurY"),
+        ]
+    );
+}
+
+#[test]
+fn pass_builder_try_build() {
+    let pass = isf::Pass::builder()
+        .target("half")
+        .dimensions("$WIDTH / 2", "floor($HEIGHT / 2.0)")
+        .try_build()
+        .unwrap();
+    assert_eq!(pass.width.as_deref(), Some("$WIDTH / 2"));
+    assert_eq!(pass.height.as_deref(), Some("floor($HEIGHT / 2.0)"));
+
+    let errors = isf::Pass::builder()
+        .persistent(true)
+        .dimensions("$WIDTH", "$HEIGHT /")
+        .try_build()
+        .unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0],
+        isf::ValidationError::PersistentPassWithoutTarget { pass: 0 }
+    );
+    match errors[1] {
+        isf::ValidationError::InvalidDimension { field: "HEIGHT", .. } => (),
+        ref err => panic!("unexpected error: {:?}", err),
+    }
+    // A pass without a target is the final pass.
+    assert!(isf::Pass::builder().try_build().is_ok());
+}
+
+#[test]
+fn persistent_string_booleans() {
+    let isf = isf::parse(
+        r#"/*{
+        "PASSES": [
+            { "TARGET": "a", "PERSISTENT": "true", "FLOAT": "NO" },
+            { "TARGET": "b", "PERSISTENT": "yes" },
+            { "PERSISTENT": "False" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let flags: Vec<_> = isf.passes.iter().map(|p| (p.persistent, p.float)).collect();
+    assert_eq!(flags, vec![(true, false), (true, false), (false, false)]);
+    let err = isf::parse(r#"/*{ "PASSES": [{ "PERSISTENT": "maybe" }] }*/"#).unwrap_err();
+    assert!(err.to_string().contains("invalid boolean \"maybe\""), "{}", err);
+}
+
+#[test]
+fn numeric_dimensions_roundtrip() {
+    let isf = isf::parse(
+        r#"/*{
+        "PASSES": [
+            { "TARGET": "a", "WIDTH": 256, "HEIGHT": "256" },
+            { "TARGET": "b", "WIDTH": 0.5, "HEIGHT": "$HEIGHT / 2" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(isf.passes[0].width.as_deref(), Some("256"));
+    assert_eq!(isf.passes[0].width, isf.passes[0].height);
+    let json = serde_json::to_string(&isf.passes).unwrap();
+    assert_eq!(
+        json,
+        concat!(
+            r#"[{"TARGET":"a","WIDTH":256,"HEIGHT":"256"},"#,
+            r#"{"TARGET":"b","WIDTH":0.5,"HEIGHT":"$HEIGHT / 2"}]"#,
+        )
+    );
+    // A numeric dimension replaced by an expression is written as a string.
+    let mut pass = isf.passes[0].clone();
+    pass.width = Some("$WIDTH".to_string());
+    let json = serde_json::to_string(&pass).unwrap();
+    assert_eq!(json, r#"{"TARGET":"a","WIDTH":"$WIDTH","HEIGHT":"256"}"#);
+
+    // How a dimension is written affects the output, so also affects equality.
+    let number = isf::parse(r#"/*{ "PASSES": [{ "WIDTH": 256 }] }*/"#).unwrap();
+    let string = isf::parse(r#"/*{ "PASSES": [{ "WIDTH": "256" }] }*/"#).unwrap();
+    assert!(number.passes[0].width_is_number);
+    assert!(!string.passes[0].width_is_number);
+    assert_ne!(number, string);
+
+    // Numeric dimensions may be set via the builder.
+    let pass = isf::Pass::builder().target("a").width(256.0).height(0.5).build();
+    let json = serde_json::to_string(&pass).unwrap();
+    assert_eq!(json, r#"{"TARGET":"a","WIDTH":256,"HEIGHT":0.5}"#);
+
+    // Persistent buffers preserve numeric dimensions too, including when upgraded to passes.
+    let mut isf = isf::parse(
+        r#"/*{
+        "PERSISTENT_BUFFERS": { "a": { "WIDTH": 256, "HEIGHT": "$HEIGHT" } },
+        "PASSES": [{ "TARGET": "a" }]
+    }*/"#,
+    )
+    .unwrap();
+    let json = serde_json::to_string(&isf.persistent_buffers).unwrap();
+    assert_eq!(json, r#"{"a":{"FLOAT":false,"WIDTH":256,"HEIGHT":"$HEIGHT"}}"#);
+    isf.upgrade_to_v2();
+    let json = serde_json::to_string(&isf.passes).unwrap();
+    assert_eq!(json, r#"[{"TARGET":"a","PERSISTENT":true,"WIDTH":256,"HEIGHT":"$HEIGHT"}]"#);
+}
@@ -0,0 +1,84 @@
+use isf::{standard_preamble, GlslVersion};
+
+#[test]
+fn preamble_per_version() {
+    let es100 = standard_preamble(GlslVersion::Es100);
+    assert!(es100.starts_with("#version 100\nprecision highp float;\n"));
+    assert!(es100.contains("varying vec2 isf_FragNormCoord;\n"));
+    assert!(es100.contains("#define IMG_NORM_PIXEL(image, coord) texture2D(image, coord)\n"));
+    assert!(es100.contains("#define IMG_SIZE(image) RENDERSIZE\n"));
+
+    let es300 = standard_preamble(GlslVersion::Es300);
+    assert!(es300.starts_with("#version 300 es\nprecision highp float;\n"));
+    assert!(es300.contains("in vec2 isf_FragNormCoord;\n"));
+    assert!(es300.contains("#define IMG_NORM_PIXEL(image, coord) texture(image, coord)\n"));
+
+    let glsl330 = standard_preamble(GlslVersion::Glsl330);
+    assert!(glsl330.starts_with("#version 330\nuniform int PASSINDEX;\n"));
+    assert!(!glsl330.contains("precision"));
+}
+
+#[test]
+fn preamble_declares_fragment_output() {
+    // GLSL ES 1.00 provides `gl_FragColor` and `texture2D` itself.
+    let es100 = standard_preamble(GlslVersion::Es100);
+    assert!(!es100.contains("isf_FragColor"));
+    assert!(!es100.contains("#define texture2D"));
+
+    for version in &[GlslVersion::Es300, GlslVersion::Glsl330] {
+        let src = standard_preamble(*version);
+        assert!(src.contains("out vec4 isf_FragColor;\n"));
+        assert!(src.contains("#define gl_FragColor isf_FragColor\n"));
+        assert!(src.contains("#define texture2D texture\n"));
+    }
+}
+
+#[test]
+fn preamble_declares_standard_uniforms() {
+    let src = standard_preamble(GlslVersion::Glsl330);
+    for decl in &[
+        "uniform int PASSINDEX;",
+        "uniform vec2 RENDERSIZE;",
+        "uniform float TIME;",
+        "uniform float TIMEDELTA;",
+        "uniform vec4 DATE;",
+        "uniform int FRAMEINDEX;",
+    ] {
+        assert!(src.contains(decl), "missing `{}`", decl);
+    }
+    for mac in &[
+        "IMG_PIXEL",
+        "IMG_NORM_PIXEL",
+        "IMG_THIS_PIXEL",
+        "IMG_THIS_NORM_PIXEL",
+    ] {
+        assert!(
+            src.contains(&format!("#define {}(", mac)),
+            "missing `{}`",
+            mac
+        );
+    }
+}
+
+#[test]
+fn preamble_names_are_reserved() {
+    for version in &[GlslVersion::Es100, GlslVersion::Es300, GlslVersion::Glsl330] {
+        let src = standard_preamble(*version);
+        for line in src.lines() {
+            let name = if let Some(rest) = line.strip_prefix("#define ") {
+                rest.split(['(', ' ']).next().unwrap()
+            } else if line.ends_with(';') && !line.starts_with("precision") {
+                line.trim_end_matches(';').rsplit(' ').next().unwrap()
+            } else {
+                continue;
+            };
+            // Names beginning with `gl_` are already reserved by GLSL itself.
+            if name.starts_with("gl_") {
+                continue;
+            }
+            assert!(isf::is_reserved_name(name), "`{}` is not reserved", name);
+        }
+    }
+    assert!(isf::is_reserved_name("inputImage"));
+    assert!(!isf::is_reserved_name("time"));
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "serde")]
+
 // Deserialize each ISF, serialize it back to JSON, then deserialize it again and make sure both
 // deserialized instances match.
 #[test]
@@ -17,9 +19,43 @@ fn roundtrip_test_files() {
                 Err(err) => panic!("err while parsing {}: {}", path.display(), err),
                 Ok(isf) => isf,
             };
+            if let Err(mismatch) = isf::roundtrip_check(&isf) {
+                panic!("{}: {}", path.display(), mismatch);
+            }
             let isf_string = serde_json::to_string_pretty(&isf).unwrap();
             let isf2 = serde_json::from_str(&isf_string).unwrap();
             assert_eq!(isf, isf2);
         }
     }
 }
+
+#[test]
+fn roundtrip_check_reports_field() {
+    let mut isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5 },
+            { "NAME": "gain", "TYPE": "float", "DEFAULT": 1.0 }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(isf::roundtrip_check(&isf), Ok(()));
+    // `NaN` cannot be represented within JSON and is written as `null`.
+    if let isf::InputType::Float(ref mut f) = isf.inputs[1].ty {
+        f.default = Some(f32::NAN);
+    }
+    let mismatch = isf::roundtrip_check(&isf).unwrap_err();
+    assert_eq!(mismatch.field, "/INPUTS/1/DEFAULT");
+}
+
+#[test]
+fn roundtrip_check_escapes_pointers() {
+    // Buffers declared via an unknown key gain the dimensions of a `PersistentBuffer` upon parsing.
+    let mut isf = isf::Isf::default();
+    let buffers = serde_json::json!({ "a/b": { "FLOAT": true } });
+    isf.extra.insert("PERSISTENT_BUFFERS".to_string(), buffers);
+    let mismatch = isf::roundtrip_check(&isf).unwrap_err();
+    assert_eq!(mismatch.field, "/PERSISTENT_BUFFERS/a~1b/WIDTH");
+    assert_eq!(mismatch.message, "the field was added");
+}
@@ -0,0 +1,257 @@
+#![cfg(feature = "schema")]
+
+use serde_json::Value;
+
+// A minimal validator supporting the subset of JSON Schema used by `isf::json_schema`.
+fn is_valid(schema: &Value, value: &Value, root: &Value) -> bool {
+    let obj = match schema.as_object() {
+        Some(obj) => obj,
+        None => return true,
+    };
+    if let Some(r) = obj.get("$ref").and_then(|r| r.as_str()) {
+        let name = r.trim_start_matches("#/definitions/");
+        return is_valid(&root["definitions"][name], value, root);
+    }
+    if let Some(ty) = obj.get("type") {
+        let types: Vec<&str> = match ty {
+            Value::Array(tys) => tys.iter().filter_map(|t| t.as_str()).collect(),
+            ty => vec![ty.as_str().unwrap()],
+        };
+        let matches = |ty: &&str| match *ty {
+            "null" => value.is_null(),
+            "boolean" => value.is_boolean(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "string" => value.is_string(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            ty => panic!("unsupported type {}", ty),
+        };
+        if !types.iter().any(matches) {
+            return false;
+        }
+    }
+    if let Some(Value::Array(variants)) = obj.get("enum") {
+        if !variants.contains(value) {
+            return false;
+        }
+    }
+    if obj.get("const").is_some_and(|c| c != value) {
+        return false;
+    }
+    if let (Some(pattern), Some(s)) = (obj.get("pattern"), value.as_str()) {
+        if !pattern_matches(pattern.as_str().unwrap(), s) {
+            return false;
+        }
+    }
+    if let Some(min) = obj.get("minimum").and_then(|m| m.as_f64()) {
+        if value.as_f64().is_some_and(|v| v < min) {
+            return false;
+        }
+    }
+    if let Value::Array(arr) = value {
+        let len = arr.len() as u64;
+        if obj.get("minItems").is_some_and(|m| len < m.as_u64().unwrap())
+            || obj.get("maxItems").is_some_and(|m| len > m.as_u64().unwrap())
+        {
+            return false;
+        }
+        if let Some(items) = obj.get("items") {
+            if !arr.iter().all(|v| is_valid(items, v, root)) {
+                return false;
+            }
+        }
+    }
+    if let Value::Object(dict) = value {
+        if let Some(Value::Array(required)) = obj.get("required") {
+            if !required.iter().all(|k| dict.contains_key(k.as_str().unwrap())) {
+                return false;
+            }
+        }
+        let properties = obj.get("properties").and_then(|p| p.as_object());
+        for (key, v) in dict {
+            let valid = match properties.and_then(|p| p.get(key)) {
+                Some(schema) => is_valid(schema, v, root),
+                None => obj
+                    .get("additionalProperties")
+                    .is_none_or(|schema| is_valid(schema, v, root)),
+            };
+            if !valid {
+                return false;
+            }
+        }
+    }
+    if let Some(Value::Array(schemas)) = obj.get("allOf") {
+        if !schemas.iter().all(|s| is_valid(s, value, root)) {
+            return false;
+        }
+    }
+    if let Some(Value::Array(schemas)) = obj.get("anyOf") {
+        if !schemas.iter().any(|s| is_valid(s, value, root)) {
+            return false;
+        }
+    }
+    if let Some(cond) = obj.get("if") {
+        if is_valid(cond, value, root) && !is_valid(&obj["then"], value, root) {
+            return false;
+        }
+    }
+    true
+}
+
+// A term of a regex pattern: a set of character ranges or a group of alternatives.
+enum Term {
+    Chars(Vec<(char, char)>),
+    Group(Vec<Vec<(Term, usize)>>),
+}
+
+// A minimal regex matcher supporting the anchored patterns used by `isf::json_schema`, i.e.
+// literals, `[...]` classes, `{n}` repetition and `(a|b)` groups.
+fn pattern_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.trim_start_matches('^').trim_end_matches('$');
+    let pattern: Vec<char> = pattern.chars().collect();
+    let seq = parse_seq(&pattern, &mut 0);
+    let text: Vec<char> = text.chars().collect();
+    seq_ends(&seq, &text, 0).contains(&text.len())
+}
+
+fn parse_seq(pattern: &[char], ix: &mut usize) -> Vec<(Term, usize)> {
+    let mut seq = vec![];
+    while *ix < pattern.len() && pattern[*ix] != ')' && pattern[*ix] != '|' {
+        let term = match pattern[*ix] {
+            '[' => {
+                let end = *ix + pattern[*ix..].iter().position(|&c| c == ']').unwrap();
+                let class = &pattern[*ix + 1..end];
+                let mut ranges = vec![];
+                let mut i = 0;
+                while i < class.len() {
+                    if i + 2 < class.len() && class[i + 1] == '-' {
+                        ranges.push((class[i], class[i + 2]));
+                        i += 3;
+                    } else {
+                        ranges.push((class[i], class[i]));
+                        i += 1;
+                    }
+                }
+                *ix = end + 1;
+                Term::Chars(ranges)
+            }
+            '(' => {
+                *ix += 1;
+                let mut alts = vec![parse_seq(pattern, ix)];
+                while pattern[*ix] == '|' {
+                    *ix += 1;
+                    alts.push(parse_seq(pattern, ix));
+                }
+                *ix += 1;
+                Term::Group(alts)
+            }
+            ch => {
+                *ix += 1;
+                Term::Chars(vec![(ch, ch)])
+            }
+        };
+        let mut count = 1;
+        if pattern.get(*ix) == Some(&'{') {
+            let end = *ix + pattern[*ix..].iter().position(|&c| c == '}').unwrap();
+            count = pattern[*ix + 1..end].iter().collect::<String>().parse().unwrap();
+            *ix = end + 1;
+        }
+        seq.push((term, count));
+    }
+    seq
+}
+
+// The positions within `text` at which a match of `seq` beginning at `start` may end.
+fn seq_ends(seq: &[(Term, usize)], text: &[char], start: usize) -> Vec<usize> {
+    let mut ends = vec![start];
+    for (term, count) in seq {
+        for _ in 0..*count {
+            ends = ends.into_iter().flat_map(|pos| term_ends(term, text, pos)).collect();
+        }
+    }
+    ends
+}
+
+fn term_ends(term: &Term, text: &[char], pos: usize) -> Vec<usize> {
+    match term {
+        Term::Chars(ranges) => match text.get(pos) {
+            Some(&c) if ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) => vec![pos + 1],
+            _ => vec![],
+        },
+        Term::Group(alts) => alts.iter().flat_map(|seq| seq_ends(seq, text, pos)).collect(),
+    }
+}
+
+fn validate(value: &Value) -> bool {
+    let schema = isf::json_schema();
+    is_valid(&schema, value, &schema)
+}
+
+#[test]
+fn schema_validates_test_files() {
+    let test_files_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files");
+    for entry in std::fs::read_dir(test_files_path).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|s| s.to_str()) != Some("fs") {
+            continue;
+        }
+        let glsl_str = std::fs::read_to_string(&path).unwrap();
+        let range = match isf::find_comment(&glsl_str) {
+            Some(range) => range,
+            None => continue,
+        };
+        let value: Value = match serde_json::from_str(&glsl_str[range]) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        assert!(validate(&value), "{}", path.display());
+    }
+}
+
+#[test]
+fn schema_rejects_invalid_inputs() {
+    let valid = serde_json::json!({
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5, "MIN": 0, "MAX": 1 },
+            { "NAME": "tint", "TYPE": "color", "DEFAULT": "#ff8000" },
+            { "NAME": "center", "TYPE": "point2D", "DEFAULT": [0.5, 0.5] }
+        ]
+    });
+    assert!(validate(&valid));
+    let unknown_type = serde_json::json!({ "INPUTS": [{ "NAME": "a", "TYPE": "vec3" }] });
+    assert!(!validate(&unknown_type));
+    let bad_point = serde_json::json!({
+        "INPUTS": [{ "NAME": "a", "TYPE": "point2D", "DEFAULT": [0.5] }]
+    });
+    assert!(!validate(&bad_point));
+    let missing_name = serde_json::json!({ "INPUTS": [{ "TYPE": "float" }] });
+    assert!(!validate(&missing_name));
+    let bad_hex = serde_json::json!({
+        "INPUTS": [{ "NAME": "a", "TYPE": "color", "DEFAULT": "#ff80" }]
+    });
+    assert!(!validate(&bad_hex));
+}
+
+#[test]
+fn schema_matches_types_case_insensitively() {
+    let valid = serde_json::json!({
+        "INPUTS": [
+            { "NAME": "a", "TYPE": "Float", "DEFAULT": 0.5 },
+            { "NAME": "b", "TYPE": "POINT2D", "DEFAULT": [0.5, 0.5] },
+            { "NAME": "c", "TYPE": "audiofft", "MAX": 64 }
+        ]
+    });
+    assert!(validate(&valid));
+    // The constraints of each type apply regardless of casing.
+    let bad_point = serde_json::json!({
+        "INPUTS": [{ "NAME": "a", "TYPE": "POINT2D", "DEFAULT": [0.5] }]
+    });
+    assert!(!validate(&bad_point));
+    let bad_float = serde_json::json!({
+        "INPUTS": [{ "NAME": "a", "TYPE": "Float", "DEFAULT": [0.5] }]
+    });
+    assert!(!validate(&bad_float));
+    let unknown_type = serde_json::json!({ "INPUTS": [{ "NAME": "a", "TYPE": "Floats" }] });
+    assert!(!validate(&unknown_type));
+}
@@ -0,0 +1,329 @@
+#![cfg(feature = "serde")]
+
+use isf::SerializeOptions;
+
+const SHADER: &str = r#"/*{
+    "INPUTS": [
+        { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.123456, "MIN": 0.1, "MAX": 10 },
+        { "NAME": "center", "TYPE": "point2D", "DEFAULT": [0.33333, 0.66666] },
+        { "NAME": "tint", "TYPE": "color", "DEFAULT": [0.1, 0.25, 0.987654, 1.0] },
+        { "NAME": "count", "TYPE": "long", "DEFAULT": 12345678, "VALUES": [12345678] }
+    ]
+}*/"#;
+
+#[test]
+fn float_precision() {
+    let isf = isf::parse(SHADER).unwrap();
+    let options = SerializeOptions {
+        float_precision: Some(2),
+        ..Default::default()
+    };
+    let json = isf::to_string_with_options(&isf, &options).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let inputs = &value["INPUTS"];
+    assert_eq!(inputs[0]["DEFAULT"], serde_json::json!(0.12));
+    assert_eq!(inputs[0]["MIN"], serde_json::json!(0.1));
+    assert_eq!(inputs[0]["MAX"], serde_json::json!(10.0));
+    assert_eq!(inputs[1]["DEFAULT"], serde_json::json!([0.33, 0.67]));
+    assert_eq!(
+        inputs[2]["DEFAULT"],
+        serde_json::json!([0.1, 0.25, 0.99, 1.0])
+    );
+    assert_eq!(inputs[3]["DEFAULT"], serde_json::json!(12345678));
+    assert!(json.contains("0.12,"));
+}
+
+#[test]
+fn float_precision_preserves_key_order_and_extras() {
+    let isf = isf::parse(
+        r#"/*{
+        "DESCRIPTION": "Test",
+        "CATEGORIES": ["Blur"],
+        "INPUTS": [{ "NAME": "amount", "TYPE": "float", "DEFAULT": 0.123456, "X_STEP": 0.001 }],
+        "X_VENDOR": { "Z": 0.123456, "A": 1 }
+    }*/"#,
+    )
+    .unwrap();
+    let options = SerializeOptions {
+        float_precision: Some(2),
+        ..Default::default()
+    };
+    let json = isf::to_string_with_options(&isf, &options).unwrap();
+    let expected = concat!(
+        r#"{"DESCRIPTION":"Test","CATEGORIES":["Blur"],"INPUTS":[{"NAME":"amount","#,
+        r#""TYPE":"float","DEFAULT":0.12,"X_STEP":0.001}],"X_VENDOR":{"Z":0.123456,"A":1}}"#,
+    );
+    assert_eq!(json, expected);
+}
+
+#[test]
+fn default_options_are_lossless() {
+    let isf = isf::parse(SHADER).unwrap();
+    let json = isf::to_string_with_options(&isf, &Default::default()).unwrap();
+    let isf2: isf::Isf = serde_json::from_str(&json).unwrap();
+    assert_eq!(isf, isf2);
+}
+
+#[test]
+fn null_fields() {
+    let isf = isf::parse(SHADER).unwrap();
+    let json = isf::to_string_with_options(&isf, &Default::default()).unwrap();
+    assert!(!json.contains("null"));
+    assert!(!json.contains("PASSES"));
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let center = value["INPUTS"][1].as_object().unwrap();
+    assert!(!center.contains_key("MIN"));
+    assert!(!center.contains_key("LABELS"));
+
+    let options = SerializeOptions {
+        emit_null_fields: true,
+        ..Default::default()
+    };
+    let json = isf::to_string_with_options(&isf, &options).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["INPUTS"][1]["MIN"], serde_json::Value::Null);
+    assert_eq!(value["INPUTS"][1]["MAX"], serde_json::Value::Null);
+    assert_eq!(value["PASSES"], serde_json::json!([]));
+    let isf2: isf::Isf = serde_json::from_str(&json).unwrap();
+    assert_eq!(isf, isf2);
+}
+
+#[test]
+fn unknown_top_level_keys() {
+    let isf = isf::parse(
+        r#"/*{
+        "DESCRIPTION": "Test",
+        "VIDVOX_THUMBNAIL": "thumb.png",
+        "com.example.tags": { "mood": ["calm"] }
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(isf.extra.len(), 2);
+    assert_eq!(isf.extra["VIDVOX_THUMBNAIL"], "thumb.png");
+    let value = serde_json::to_value(&isf).unwrap();
+    assert_eq!(value["com.example.tags"]["mood"][0], "calm");
+    let isf2: isf::Isf = serde_json::from_value(value).unwrap();
+    assert_eq!(isf, isf2);
+
+    // An empty map must not add any keys.
+    let isf = isf::parse(SHADER).unwrap();
+    assert!(isf.extra.is_empty());
+    let value = serde_json::to_value(&isf).unwrap();
+    let keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+    assert_eq!(keys, vec!["INPUTS"]);
+}
+
+#[test]
+fn empty_unknown_keys_round_trip() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [{ "NAME": "amount", "TYPE": "float", "X_HINT": null, "X_STOPS": [] }],
+        "X_VENDOR": null,
+        "X_TAGS": [],
+        "X_META": {}
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(isf.extra.len(), 3);
+    assert_eq!(isf.inputs[0].extra.len(), 2);
+    for emit_null_fields in [false, true] {
+        let options = SerializeOptions {
+            emit_null_fields,
+            ..Default::default()
+        };
+        let json = isf::to_string_with_options(&isf, &options).unwrap();
+        assert!(json.contains(r#""X_VENDOR":null"#));
+        assert!(json.contains(r#""X_HINT":null"#));
+        let isf2: isf::Isf = serde_json::from_str(&json).unwrap();
+        assert_eq!(isf, isf2);
+    }
+}
+
+#[test]
+fn unknown_input_keys() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5, "UI_WIDGET": "knob" },
+            { "NAME": "center", "TYPE": "point2D" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(isf.inputs[0].extra["UI_WIDGET"], "knob");
+    assert!(isf.inputs[1].extra.is_empty());
+    let json = isf::to_string_with_options(&isf, &Default::default()).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["INPUTS"][0]["UI_WIDGET"], "knob");
+    let center = value["INPUTS"][1].as_object().unwrap();
+    let keys: Vec<_> = center.keys().cloned().collect();
+    assert_eq!(keys, vec!["NAME", "TYPE"]);
+    let isf2: isf::Isf = serde_json::from_str(&json).unwrap();
+    assert_eq!(isf, isf2);
+}
+
+#[test]
+fn values_and_labels_only_on_long_inputs() {
+    let isf = isf::parse(SHADER).unwrap();
+    let value = serde_json::to_value(&isf).unwrap();
+    let amount = value["INPUTS"][0].as_object().unwrap();
+    assert!(!amount.contains_key("VALUES"));
+    assert!(!amount.contains_key("LABELS"));
+    assert_eq!(value["INPUTS"][3]["VALUES"], serde_json::json!([12345678]));
+
+    let options = SerializeOptions {
+        emit_null_fields: true,
+        ..Default::default()
+    };
+    let json = isf::to_string_with_options(&isf, &options).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["INPUTS"][0]["VALUES"], serde_json::json!([]));
+    assert_eq!(value["INPUTS"][0]["LABELS"], serde_json::json!([]));
+}
+
+#[test]
+fn does_not_emit_extra_fields() {
+    let mut isf = isf::Isf::default();
+    isf.passes.push(isf::Pass::to_screen());
+    isf.passes.push(isf::Pass::to_target("blur"));
+    let value = serde_json::to_value(&isf).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "INPUTS": [],
+            "PASSES": [{}, { "TARGET": "blur" }]
+        })
+    );
+    isf.passes.clear();
+    assert_eq!(serde_json::to_string(&isf).unwrap(), r#"{"INPUTS":[]}"#);
+
+    // Hosts requiring every key may still request them.
+    let options = SerializeOptions {
+        emit_null_fields: true,
+        ..Default::default()
+    };
+    let json = isf::to_string_with_options(&isf, &options).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["ISFVSN"], serde_json::Value::Null);
+    assert_eq!(value["CATEGORIES"], serde_json::json!([]));
+    assert_eq!(value["IMPORTED"], serde_json::json!({}));
+    assert_eq!(value["LABEL"], serde_json::Value::Null);
+    assert_eq!(value["CREDIT"], serde_json::Value::Null);
+    let keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+    let expected = [
+        "ISFVSN", "VSN", "DESCRIPTION", "LABEL", "CREDIT", "CATEGORIES", "INPUTS", "PASSES",
+        "IMPORTED",
+    ];
+    assert_eq!(keys, expected);
+
+    // Every pass key is written too.
+    isf.passes.push(isf::Pass::to_target("blur"));
+    let json = isf::to_string_with_options(&isf, &options).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        value["PASSES"][0],
+        serde_json::json!({
+            "TARGET": "blur",
+            "PERSISTENT": false,
+            "FLOAT": false,
+            "WIDTH": null,
+            "HEIGHT": null
+        })
+    );
+    assert_eq!(serde_json::from_str::<isf::Isf>(&json).unwrap(), isf);
+
+    // Required keys are kept when empty.
+    isf.passes.clear();
+    let json = isf::to_string_with_options(&isf, &Default::default()).unwrap();
+    assert_eq!(json, r#"{"INPUTS":[]}"#);
+}
+
+#[test]
+fn write_comment() {
+    let glsl_str = "#version 150\n/*!\n{ \"INPUTS\": [] }\n!*/\nvoid main() {\n    // */\n}\n";
+    let mut isf = isf::parse(glsl_str).unwrap();
+    isf.description = Some("Closes */ early".to_string());
+    let amount = isf::Input::builder("amount", isf::InputType::Float(Default::default()));
+    isf.inputs.push(amount.build());
+    let written = isf::write_comment(glsl_str, &isf).unwrap();
+    assert_eq!(isf::parse(&written).unwrap(), isf);
+    assert!(written.starts_with("#version 150\n/*!\n{"));
+    assert!(written.ends_with("}\n!*/\nvoid main() {\n    // */\n}\n"));
+    assert!(!written.contains("Closes */"));
+    let (_, body) = isf::parse_with_source(&written).unwrap();
+    assert_eq!(body.src, "\nvoid main() {\n    // */\n}\n");
+
+    // A comment is inserted when the source has none.
+    let written = isf::write_comment("void main() {}", &isf).unwrap();
+    assert_eq!(isf::parse(&written).unwrap(), isf);
+    assert!(written.ends_with("*/\nvoid main() {}"));
+}
+
+#[test]
+fn input_serialization_is_stable() {
+    let isf = isf::parse(
+        r#"/*{ "INPUTS": [
+        { "NAME": "p", "TYPE": "point2D", "DEFAULT": [0.5, 1], "COORDINATE_SPACE": "pixel" },
+        { "NAME": "l", "TYPE": "long", "VALUES": [1, 2], "LABELS": ["a", "b"], "X": true }
+    ] }*/"#,
+    )
+    .unwrap();
+    let json = serde_json::to_string(&isf.inputs).unwrap();
+    let expected = concat!(
+        r#"[{"NAME":"p","TYPE":"point2D","DEFAULT":[0.5,1.0],"COORDINATE_SPACE":"pixel"},"#,
+        r#"{"NAME":"l","TYPE":"long","VALUES":[1,2],"LABELS":["a","b"],"X":true}]"#,
+    );
+    assert_eq!(json, expected);
+
+    // Fields without a value are only written when requested.
+    let options = SerializeOptions {
+        emit_null_fields: true,
+        ..Default::default()
+    };
+    let json = isf::to_string_with_options(&isf, &options).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let expected = serde_json::json!({
+        "NAME": "l",
+        "LABEL": null,
+        "TYPE": "long",
+        "DEFAULT": null,
+        "MIN": null,
+        "MAX": null,
+        "IDENTITY": null,
+        "VALUES": [1, 2],
+        "LABELS": ["a", "b"],
+        "X": true
+    });
+    assert_eq!(value["INPUTS"][1], expected);
+}
+
+#[test]
+fn json_value_conversions() {
+    use std::convert::TryFrom;
+    let document = serde_json::json!({
+        "name": "Glow",
+        "isf": {
+            "DESCRIPTION": "Makes things glow",
+            "INPUTS": [{ "NAME": "amount", "TYPE": "float", "DEFAULT": 0.5 }],
+            "PASSES": [{ "TARGET": "blur" }, {}]
+        }
+    });
+    let isf = isf::Isf::try_from(document["isf"].clone()).unwrap();
+    assert_eq!(isf, isf::parse(&format!("/*{}*/", document["isf"])).unwrap());
+    let value = serde_json::Value::try_from(&isf).unwrap();
+    assert_eq!(isf::Isf::try_from(value.clone()).unwrap(), isf);
+    let reparsed = isf::Isf::try_from(value.clone()).unwrap();
+    assert_eq!(serde_json::Value::try_from(&reparsed).unwrap(), value);
+    assert_eq!(value["INPUTS"][0]["DEFAULT"], serde_json::json!(0.5));
+    assert!(isf::Isf::try_from(serde_json::json!({ "INPUTS": 1 })).is_err());
+
+    // Paths that are not valid unicode cannot be represented.
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        let mut isf = isf;
+        let path = std::ffi::OsStr::from_bytes(b"noise\xff.png").into();
+        isf.imported.insert("noise".to_string(), isf::ImageImport { path });
+        assert!(serde_json::Value::try_from(&isf).is_err());
+    }
+}
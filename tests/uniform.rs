@@ -0,0 +1,152 @@
+#![cfg(feature = "serde")]
+
+use isf::{InputValue, UniformType};
+use std::collections::BTreeMap;
+
+const SHADER: &str = r#"/*{
+    "INPUTS": [
+        { "NAME": "enabled", "TYPE": "bool" },
+        { "NAME": "center", "TYPE": "point2D" },
+        { "NAME": "inputImage", "TYPE": "image" },
+        { "NAME": "count", "TYPE": "long" },
+        { "NAME": "tint", "TYPE": "color" },
+        { "NAME": "amount", "TYPE": "float" },
+        { "NAME": "reset", "TYPE": "event" }
+    ]
+}*/"#;
+
+#[test]
+fn std140_offsets() {
+    let layout = isf::parse(SHADER).unwrap().uniform_layout();
+    let offsets: Vec<_> = layout
+        .uniforms
+        .iter()
+        .map(|u| (&u.name[..], u.ty, u.offset))
+        .collect();
+    assert_eq!(
+        offsets,
+        vec![
+            ("enabled", UniformType::Bool, 0),
+            ("center", UniformType::Vec2, 8),
+            ("count", UniformType::Int, 16),
+            ("tint", UniformType::Vec4, 32),
+            ("amount", UniformType::Float, 48),
+            ("reset", UniformType::Bool, 52),
+        ]
+    );
+    assert_eq!(layout.size, 64);
+}
+
+#[test]
+fn pack_and_read_values() {
+    let layout = isf::parse(SHADER).unwrap().uniform_layout();
+    let mut values = BTreeMap::new();
+    values.insert("enabled".to_string(), InputValue::Bool(true));
+    values.insert("center".to_string(), InputValue::Point2d([0.25, -0.5]));
+    values.insert("count".to_string(), InputValue::Long(-3));
+    values.insert(
+        "tint".to_string(),
+        InputValue::Color([1.0, 0.5, 0.25, 0.75]),
+    );
+    values.insert("amount".to_string(), InputValue::Float(0.125));
+    values.insert("reset".to_string(), InputValue::Event(true));
+    let bytes = layout.pack(&values).unwrap();
+    assert_eq!(bytes.len(), layout.size);
+    assert_eq!(&bytes[8..12], &0.25f32.to_le_bytes());
+    assert_eq!(layout.read_values(&bytes), values);
+}
+
+#[test]
+fn pack_out_of_bounds() {
+    let mut layout = isf::parse(SHADER).unwrap().uniform_layout();
+    let mut values = BTreeMap::new();
+    values.insert("enabled".to_string(), InputValue::Bool(true));
+    layout.uniforms[0].offset = layout.size;
+    let err = layout.pack(&values).unwrap_err();
+    assert_eq!(err.name, "enabled");
+    assert_eq!(err.offset, layout.size);
+}
+
+#[test]
+fn read_values_truncated() {
+    let layout = isf::parse(SHADER).unwrap().uniform_layout();
+    let values = layout.read_values(&[0u8; 20]);
+    let names: Vec<_> = values.keys().map(|s| &s[..]).collect();
+    assert_eq!(names, vec!["center", "count", "enabled"]);
+}
+
+#[test]
+fn color_arity() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "rgb", "TYPE": "color", "DEFAULT": [1, 0, 0], "MAX": [1, 1, 1] },
+            { "NAME": "amount", "TYPE": "float" },
+            { "NAME": "rgba", "TYPE": "color", "DEFAULT": [1, 0, 0, 1] },
+            { "NAME": "mixed", "TYPE": "color", "DEFAULT": [1, 0, 0], "MAX": [1, 1, 1, 1] },
+            { "NAME": "unknown", "TYPE": "color" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let decls: Vec<_> = isf
+        .inputs
+        .iter()
+        .filter_map(|i| i.uniform_declaration())
+        .collect();
+    assert_eq!(
+        decls,
+        vec![
+            "uniform vec3 rgb;",
+            "uniform float amount;",
+            "uniform vec4 rgba;",
+            "uniform vec4 mixed;",
+            "uniform vec4 unknown;",
+        ]
+    );
+
+    // The layout must agree with the declarations.
+    let layout = isf.uniform_layout();
+    assert_eq!(layout.uniforms[0].ty, UniformType::Vec3);
+    // A scalar may occupy the space following a `vec3`.
+    let offsets: Vec<_> = layout.uniforms.iter().map(|u| u.offset).collect();
+    assert_eq!(offsets, vec![0, 12, 16, 32, 48]);
+    let mut values = BTreeMap::new();
+    values.insert("rgb".to_string(), InputValue::Color([0.5, 0.25, 1.0, 1.0]));
+    values.insert("amount".to_string(), InputValue::Float(2.0));
+    let read = layout.read_values(&layout.pack(&values).unwrap());
+    assert_eq!(read["rgb"], values["rgb"]);
+    assert_eq!(read["amount"], values["amount"]);
+}
+
+#[test]
+fn uniform_declarations() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "inputImage", "TYPE": "image" },
+            { "NAME": "flash", "TYPE": "event" },
+            { "NAME": "invert", "TYPE": "bool" },
+            { "NAME": "mode", "TYPE": "long" },
+            { "NAME": "amount", "TYPE": "float" },
+            { "NAME": "center", "TYPE": "point2D" },
+            { "NAME": "tint", "TYPE": "color" },
+            { "NAME": "wave", "TYPE": "audio" },
+            { "NAME": "fft", "TYPE": "audioFFT" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let expected = "\
+uniform sampler2D inputImage;
+uniform bool flash;
+uniform bool invert;
+uniform int mode;
+uniform float amount;
+uniform vec2 center;
+uniform vec4 tint;
+uniform sampler2D wave;
+uniform sampler2D fft;
+";
+    assert_eq!(isf.uniform_declarations(), expected);
+}
@@ -0,0 +1,214 @@
+#![cfg(feature = "serde")]
+
+use isf::ValidationError;
+
+#[test]
+fn validate_long_labels() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "mode", "TYPE": "long", "VALUES": [0, 1, 2], "LABELS": ["A", "B"] },
+            { "NAME": "count", "TYPE": "long", "VALUES": [0, 1] }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(
+        isf.validate(),
+        Err(vec![ValidationError::LongLabelCountMismatch {
+            input: "mode".to_string(),
+            values: 3,
+            labels: 2,
+        }])
+    );
+}
+
+#[test]
+fn validate_with_body() {
+    let glsl_str = r#"/*{
+    "INPUTS": [
+        { "NAME": "inputImage", "TYPE": "image" },
+        { "NAME": "amount", "TYPE": "float" },
+        { "NAME": "unused", "TYPE": "bool" }
+    ],
+    "IMPORTED": {
+        "lut": { "PATH": "lut.png" },
+        "noise": { "PATH": "noise.png" }
+    },
+    "PASSES": [{ "TARGET": "a" }, { "TARGET": "b" }]
+}*/
+uniform float gain;
+uniform float TIME;
+void main() {
+    // unused
+    vec4 c = IMG_THIS_PIXEL(inputImage) * amount * gain;
+    gl_FragColor = c + IMG_THIS_PIXEL(lut) + IMG_THIS_PIXEL(a) + IMG_THIS_PIXEL(b);
+}
+"#;
+    let (isf, body) = isf::parse_with_source(glsl_str).unwrap();
+    let errors = isf.validate_with_body(body.src).unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::UnusedInput {
+                input: "unused".to_string()
+            },
+            ValidationError::UndeclaredUniform {
+                name: "gain".to_string()
+            },
+            ValidationError::PassIndexUnused { passes: 2 },
+            ValidationError::UnusedImport {
+                name: "noise".to_string()
+            },
+            ValidationError::PassCycle { passes: vec![0, 1] },
+        ]
+    );
+}
+
+#[test]
+fn validate_with_body_ok() {
+    let glsl_str = r#"/*{
+    "INPUTS": [{ "NAME": "inputImage", "TYPE": "image" }]
+}*/
+void main() {
+    gl_FragColor = IMG_THIS_PIXEL(inputImage);
+}
+"#;
+    let (isf, body) = isf::parse_with_source(glsl_str).unwrap();
+    assert_eq!(isf.validate_with_body(body.src), Ok(()));
+}
+
+#[test]
+fn validate_ranges_and_passes() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "amount", "TYPE": "float", "DEFAULT": 1.5, "MIN": 0.0, "MAX": 1.0 },
+            { "NAME": "gain", "TYPE": "float", "DEFAULT": 0.5, "MIN": 2.0, "MAX": 1.0 },
+            { "NAME": "mode", "TYPE": "long", "DEFAULT": 3, "VALUES": [0, 1] },
+            { "NAME": "ok", "TYPE": "float", "DEFAULT": 0.5, "MIN": 0.0, "MAX": 1.0 }
+        ],
+        "PASSES": [{ "TARGET": "a", "WIDTH": "$WIDTH /" }, {}]
+    }*/"#,
+    )
+    .unwrap();
+    let errors = isf.validate().unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::DefaultOutOfRange {
+                input: "amount".to_string(),
+                default: 1.5,
+                min: 0.0,
+                max: 1.0,
+            },
+            ValidationError::MinMaxSwapped {
+                input: "gain".to_string(),
+                min: 2.0,
+                max: 1.0,
+            },
+            ValidationError::DefaultNotInValues {
+                input: "mode".to_string(),
+                default: 3,
+            },
+            ValidationError::InvalidDimension {
+                pass: 0,
+                field: "WIDTH",
+                err: isf::ExprError::UnexpectedEnd,
+            },
+        ]
+    );
+}
+
+#[test]
+fn inverted_bounds_per_axis() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "pos", "TYPE": "point2D", "MIN": [1.0, 5.0], "MAX": [0.0, 2.0] },
+            { "NAME": "off", "TYPE": "point2D", "MIN": [0.0, 3.0], "MAX": [1.0, 2.0] },
+            { "NAME": "count", "TYPE": "long", "MIN": 10, "MAX": 1 },
+            { "NAME": "ok", "TYPE": "point2D", "MIN": [0.0, 0.0], "MAX": [1.0, 1.0] }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let swapped = |input: &str, axis, min, max| ValidationError::PointMinMaxSwapped {
+        input: input.to_string(),
+        axis,
+        min,
+        max,
+    };
+    assert_eq!(
+        isf.inputs[0].inverted_bounds(),
+        vec![swapped("pos", 'x', 1.0, 0.0), swapped("pos", 'y', 5.0, 2.0)]
+    );
+    assert_eq!(isf.inputs[1].inverted_bounds(), vec![swapped("off", 'y', 3.0, 2.0)]);
+    assert!(isf.inputs[3].inverted_bounds().is_empty());
+    let errors = isf.validate().unwrap_err();
+    assert_eq!(errors.len(), 4);
+    assert_eq!(
+        errors[3],
+        ValidationError::MinMaxSwapped {
+            input: "count".to_string(),
+            min: 10.0,
+            max: 1.0,
+        }
+    );
+}
+
+#[test]
+fn validate_pass_targets() {
+    let isf = isf::parse(
+        r#"/*{
+        "PASSES": [
+            { "PERSISTENT": true },
+            {},
+            { "TARGET": "ok" },
+            {}
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    assert_eq!(
+        isf.validate(),
+        Err(vec![
+            ValidationError::PersistentPassWithoutTarget { pass: 0 },
+            ValidationError::IntermediatePassWithoutTarget { pass: 1 },
+        ])
+    );
+}
+
+#[test]
+fn validate_input_names() {
+    let isf = isf::parse(
+        r#"/*{
+        "INPUTS": [
+            { "NAME": "inputImage", "TYPE": "image" },
+            { "NAME": "TIME", "TYPE": "float" },
+            { "NAME": "gl_FragColor", "TYPE": "color" },
+            { "NAME": "1stInput", "TYPE": "bool" },
+            { "NAME": "my__input", "TYPE": "bool" },
+            { "NAME": "_ok2", "TYPE": "bool" }
+        ]
+    }*/"#,
+    )
+    .unwrap();
+    let name = |s: &str| s.to_string();
+    assert_eq!(
+        isf.validate(),
+        Err(vec![
+            ValidationError::ReservedInputName { input: name("TIME") },
+            ValidationError::InvalidInputName { input: name("gl_FragColor") },
+            ValidationError::InvalidInputName { input: name("1stInput") },
+            ValidationError::InvalidInputName { input: name("my__input") },
+        ])
+    );
+    let isf = isf::parse(r#"/*{ "INPUTS": [{ "NAME": "inputImage", "TYPE": "float" }] }*/"#);
+    assert_eq!(
+        isf.unwrap().validate(),
+        Err(vec![ValidationError::ReservedInputName { input: name("inputImage") }])
+    );
+    assert!(isf::glsl::is_identifier("amount"));
+    assert!(!isf::glsl::is_identifier(""));
+}